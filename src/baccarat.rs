@@ -0,0 +1,398 @@
+//! Punto Banco baccarat: the drawing tableau, bet settlement and the scoreboards kept
+//! beside the table.
+
+use crate::cards::{Card, Rank, Shoe};
+use std::fmt;
+use std::str::FromStr;
+
+/// Baccarat is dealt from an eight deck shoe.
+pub const DECKS: usize = 8;
+
+/// The cut card goes in near the back of the shoe, leaving about 20 cards undealt.
+pub const PENETRATION: f64 = 0.95;
+
+/// Rows in the bead plate and big road grids.
+pub const SCOREBOARD_ROWS: usize = 6;
+
+/// Whether the player hand draws a third card, by its two card total (0 to 9). `D` draws
+/// and `S` stands; an 8 or 9 is a natural and ends the coup before anyone draws.
+const PLAYER_TABLEAU: &str = "DDDDDDSSSS";
+
+/// Whether the banker draws when the player has drawn, by the banker's two card total
+/// (rows 0 to 7) and the value of the player's third card (columns 0 to 9).
+const BANKER_TABLEAU: [&str; 8] = [
+    "DDDDDDDDDD", // 0
+    "DDDDDDDDDD", // 1
+    "DDDDDDDDDD", // 2
+    "DDDDDDDDSD", // 3
+    "SSDDDDDDSS", // 4
+    "SSSSDDDDSS", // 5
+    "SSSSSSDDSS", // 6
+    "SSSSSSSSSS", // 7
+];
+
+/// A card's baccarat value: Aces count 1, tens and picture cards 0.
+pub fn card_value(rank: Rank) -> u8 {
+    match rank {
+        Rank::Ace => 1,
+        Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 0,
+        _ => rank as u8 + 2,
+    }
+}
+
+/// The value of a hand: the last digit of its card values added up.
+pub fn total(cards: &[Card]) -> u8 {
+    cards.iter().map(|card| card_value(card.rank)).sum::<u8>() % 10
+}
+
+/// Whether the player hand draws a third card on `player_total`.
+pub fn player_draws(player_total: u8) -> bool {
+    PLAYER_TABLEAU.as_bytes()[player_total as usize] == b'D'
+}
+
+/// Whether the banker draws a third card on `banker_total`. When the player stood, the
+/// banker plays like the player; otherwise the tableau looks at the player's third card.
+pub fn banker_draws(banker_total: u8, player_third_card: Option<Card>) -> bool {
+    match player_third_card {
+        None => player_draws(banker_total),
+        Some(card) => BANKER_TABLEAU
+            .get(banker_total as usize)
+            .is_some_and(|row| row.as_bytes()[card_value(card.rank) as usize] == b'D'),
+    }
+}
+
+/// The side of the table a coup goes to, and what can be bet on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Player,
+    Banker,
+    Tie,
+}
+
+impl Side {
+    /// The change to the balance from a bet of `stake` on this side when `winner` wins.
+    /// Player pays 1:1, Banker 1:1 less 5% commission (rounded down in the house's favour)
+    /// and Tie 8:1. Player and Banker bets push on a tie.
+    pub fn settle(self, stake: u32, winner: Side) -> i32 {
+        match (self, winner) {
+            (Side::Player, Side::Player) => stake as i32,
+            (Side::Banker, Side::Banker) => (stake * 95 / 100) as i32,
+            (Side::Tie, Side::Tie) => 8 * stake as i32,
+            (Side::Player | Side::Banker, Side::Tie) => 0,
+            _ => -(stake as i32),
+        }
+    }
+
+    /// The scoreboard letter: P, B or T.
+    pub fn letter(self) -> char {
+        match self {
+            Side::Player => 'P',
+            Side::Banker => 'B',
+            Side::Tie => 'T',
+        }
+    }
+}
+
+impl fmt::Display for Side {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Side::Player => "Player",
+            Side::Banker => "Banker",
+            Side::Tie => "Tie",
+        })
+    }
+}
+
+impl FromStr for Side {
+    type Err = String;
+
+    /// Accepts the names or their first letters, in any case ("punto" and "banco" too).
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "p" | "player" | "punto" => Ok(Side::Player),
+            "b" | "banker" | "banco" => Ok(Side::Banker),
+            "t" | "tie" => Ok(Side::Tie),
+            _ => Err(format!(
+                "unknown bet '{}', expected player, banker or tie",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// One coup: the player and banker hands as dealt out in full.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coup {
+    pub player: Vec<Card>,
+    pub banker: Vec<Card>,
+}
+
+impl Coup {
+    /// Deals a coup from the shoe: two cards each, alternating from the player, then any
+    /// third cards the tableau calls for.
+    pub fn deal(shoe: &mut Shoe) -> Self {
+        let mut coup = Coup {
+            player: Vec::new(),
+            banker: Vec::new(),
+        };
+        for _ in 0..2 {
+            coup.player.push(shoe.draw());
+            coup.banker.push(shoe.draw());
+        }
+        if coup.is_natural() {
+            return coup;
+        }
+        let mut player_third_card = None;
+        if player_draws(coup.player_total()) {
+            let card = shoe.draw();
+            coup.player.push(card);
+            player_third_card = Some(card);
+        }
+        if banker_draws(coup.banker_total(), player_third_card) {
+            coup.banker.push(shoe.draw());
+        }
+        coup
+    }
+
+    pub fn player_total(&self) -> u8 {
+        total(&self.player)
+    }
+
+    pub fn banker_total(&self) -> u8 {
+        total(&self.banker)
+    }
+
+    /// Either side's first two cards make 8 or 9.
+    pub fn is_natural(&self) -> bool {
+        total(&self.player[..2]) >= 8 || total(&self.banker[..2]) >= 8
+    }
+
+    pub fn winner(&self) -> Side {
+        let (player, banker) = (self.player_total(), self.banker_total());
+        if player > banker {
+            Side::Player
+        } else if banker > player {
+            Side::Banker
+        } else {
+            Side::Tie
+        }
+    }
+}
+
+/// One entry of the big road: a Player or Banker win and the ties that came after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoadCell {
+    pub winner: Side,
+    pub ties: u32,
+}
+
+/// The results of every coup since the shoe was shuffled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Scoreboard {
+    results: Vec<Side>,
+}
+
+impl Scoreboard {
+    pub fn record(&mut self, winner: Side) {
+        self.results.push(winner);
+    }
+
+    pub fn clear(&mut self) {
+        self.results.clear();
+    }
+
+    pub fn results(&self) -> &[Side] {
+        &self.results
+    }
+
+    /// The bead plate: every result in order, filling each column of `rows` from the top
+    /// before moving to the next. Returned as rows for printing.
+    pub fn bead_plate(&self, rows: usize) -> Vec<Vec<Option<Side>>> {
+        let columns = self.results.len().div_ceil(rows);
+        let mut grid = vec![vec![None; columns]; rows];
+        for (index, &side) in self.results.iter().enumerate() {
+            grid[index % rows][index / rows] = Some(side);
+        }
+        grid
+    }
+
+    /// The big road: each run of Player or Banker wins goes down a column, and a new
+    /// column starts whenever the winner changes. Ties are marked on the cell before them
+    /// (or the first cell, for ties before any win). A run longer than the grid, or one
+    /// that reaches a cell already taken, turns right along its row: the "dragon tail".
+    /// Returned as rows for printing.
+    pub fn big_road(&self, rows: usize) -> Vec<Vec<Option<RoadCell>>> {
+        let mut grid: Vec<Vec<Option<RoadCell>>> = vec![Vec::new(); rows];
+        let taken = |grid: &[Vec<Option<RoadCell>>], row: usize, column: usize| {
+            grid[row].get(column).is_some_and(Option::is_some)
+        };
+        let mut leading_ties = 0;
+        // The cell last written, the column its run started in and whether it has turned
+        let mut last: Option<(usize, usize)> = None;
+        let mut run_column = 0;
+        let mut turned = false;
+        for &side in &self.results {
+            if side == Side::Tie {
+                match last {
+                    Some((row, column)) => {
+                        if let Some(cell) = grid[row][column].as_mut() {
+                            cell.ties += 1;
+                        }
+                    }
+                    None => leading_ties += 1,
+                }
+                continue;
+            }
+            let (row, column) = match last {
+                None => (0, 0),
+                Some((row, column)) if grid[row][column].is_some_and(|c| c.winner == side) => {
+                    if !turned && row + 1 < rows && !taken(&grid, row + 1, column) {
+                        (row + 1, column)
+                    } else {
+                        turned = true;
+                        (row, column + 1)
+                    }
+                }
+                Some(_) => {
+                    run_column += 1;
+                    while taken(&grid, 0, run_column) {
+                        run_column += 1;
+                    }
+                    turned = false;
+                    (0, run_column)
+                }
+            };
+            for cells in grid.iter_mut() {
+                if cells.len() <= column {
+                    cells.resize(column + 1, None);
+                }
+            }
+            grid[row][column] = Some(RoadCell {
+                winner: side,
+                ties: std::mem::take(&mut leading_ties),
+            });
+            last = Some((row, column));
+        }
+        grid
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cards;
+
+    fn board(results: &str) -> Scoreboard {
+        let mut board = Scoreboard::default();
+        for letter in results.chars() {
+            board.record(letter.to_string().parse().unwrap());
+        }
+        board
+    }
+
+    /// The big road as text, one string per row, with '.' for an empty cell.
+    fn road(board: &Scoreboard, rows: usize) -> Vec<String> {
+        board
+            .big_road(rows)
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell.map_or('.', |cell| cell.winner.letter()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_totals() {
+        assert_eq!(total(&cards("KH 9S")), 9);
+        assert_eq!(total(&cards("7H 8S")), 5);
+        assert_eq!(total(&cards("AH TS QD")), 1);
+    }
+
+    #[test]
+    fn test_player_tableau() {
+        for total in 0..=5 {
+            assert!(player_draws(total));
+        }
+        for total in 6..=9 {
+            assert!(!player_draws(total));
+        }
+    }
+
+    #[test]
+    fn test_banker_tableau() {
+        let third = |s: &str| Some(s.parse::<Card>().unwrap());
+        // Without a player third card the banker draws on 0-5 like the player
+        assert!(banker_draws(5, None));
+        assert!(!banker_draws(6, None));
+        assert!(banker_draws(2, third("9C")));
+        assert!(banker_draws(3, third("9C")));
+        assert!(!banker_draws(3, third("8C")));
+        assert!(!banker_draws(4, third("AC")));
+        assert!(banker_draws(4, third("2C")));
+        assert!(banker_draws(4, third("7C")));
+        assert!(!banker_draws(5, third("3C")));
+        assert!(banker_draws(5, third("4C")));
+        assert!(!banker_draws(6, third("5C")));
+        assert!(banker_draws(6, third("6C")));
+        assert!(!banker_draws(6, third("KC")));
+        assert!(!banker_draws(7, third("6C")));
+    }
+
+    #[test]
+    fn test_deal_coup() {
+        // Player 2 3 (5) draws a 4, banker K 4 (4) draws on a player 4 and gets a 5
+        let mut shoe = Shoe::from_cards(cards("2H KS 3D 4C 4H 5S 9D"), Shoe::new(8, 1.0));
+        let coup = Coup::deal(&mut shoe);
+        assert_eq!(coup.player, cards("2H 3D 4H"));
+        assert_eq!(coup.banker, cards("KS 4C 5S"));
+        assert_eq!(coup.winner(), Side::Tie);
+        assert_eq!(shoe.remaining(), 1);
+
+        // A banker natural ends the coup
+        let mut shoe = Shoe::from_cards(cards("2H 5S 3D 3C 9H"), Shoe::new(8, 1.0));
+        let coup = Coup::deal(&mut shoe);
+        assert!(coup.is_natural());
+        assert_eq!((coup.player.len(), coup.banker.len()), (2, 2));
+        assert_eq!(coup.winner(), Side::Banker);
+    }
+
+    #[test]
+    fn test_settle_bets() {
+        assert_eq!(Side::Player.settle(10, Side::Player), 10);
+        assert_eq!(Side::Banker.settle(20, Side::Banker), 19);
+        assert_eq!(Side::Banker.settle(10, Side::Banker), 9);
+        assert_eq!(Side::Tie.settle(10, Side::Tie), 80);
+        assert_eq!(Side::Banker.settle(10, Side::Tie), 0);
+        assert_eq!(Side::Tie.settle(10, Side::Player), -10);
+        assert_eq!("Banco".parse(), Ok(Side::Banker));
+        assert!("dragon".parse::<Side>().is_err());
+    }
+
+    #[test]
+    fn test_bead_plate() {
+        let plate = board("PBTPPPB").bead_plate(3);
+        assert_eq!(
+            plate[0],
+            vec![Some(Side::Player), Some(Side::Player), Some(Side::Banker)]
+        );
+        assert_eq!(plate[1], vec![Some(Side::Banker), Some(Side::Player), None]);
+        assert_eq!(plate[2], vec![Some(Side::Tie), Some(Side::Player), None]);
+    }
+
+    #[test]
+    fn test_big_road() {
+        let board = board("TPPTTBPPP");
+        assert_eq!(road(&board, 6)[..3], ["PBP", "P.P", "..P"]);
+        let cells = board.big_road(6);
+        assert_eq!(cells[0][0].unwrap().ties, 1);
+        assert_eq!(cells[1][0].unwrap().ties, 2);
+
+        // A long run turns right at the bottom, and the next run starts after its tail
+        let dragon = self::board("BBBBPB");
+        assert_eq!(road(&dragon, 3), ["BPB", "B..", "BB."]);
+        let dragon = self::board("BBBBBPPPB");
+        assert_eq!(road(&dragon, 3), ["BPB", "BPP", "BBB"]);
+    }
+}
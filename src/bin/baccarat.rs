@@ -0,0 +1,218 @@
+use colored::*;
+use games::baccarat::{
+    total, Coup, RoadCell, Scoreboard, Side, DECKS, PENETRATION, SCOREBOARD_ROWS,
+};
+use games::cards::{format_hand, Card, Shoe};
+use games::cli::format_net;
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    println!("Welcome to Punto Banco baccarat!");
+    println!(
+        "Player pays 1:1, Banker 1:1 less 5% commission and Tie 8:1. Player and Banker bets push on a tie."
+    );
+    let mut shoe = Shoe::new(DECKS, PENETRATION);
+    let mut scoreboard = Scoreboard::default();
+    let mut balance: u32 = 100;
+    loop {
+        println!("--------------------------------------------------------------");
+        if !scoreboard.results().is_empty() {
+            print_scoreboard(&scoreboard);
+        }
+        println!(
+            "{} {}",
+            "Your balance: $".green(),
+            balance.to_string().green()
+        );
+        if balance == 0 {
+            println!("{}", "You're out of money. Game over!".red());
+            break;
+        }
+        if shoe.cut_card_reached() {
+            println!(
+                "{}",
+                "Cut card reached, shuffling a new shoe and clearing the scoreboard.".yellow()
+            );
+            shoe.reshuffle();
+            scoreboard.clear();
+        }
+
+        let Some(bets) = ask_bets(balance) else {
+            break;
+        };
+        let coup = deal(&mut shoe);
+        let winner = coup.winner();
+        scoreboard.record(winner);
+        let result = match winner {
+            Side::Tie => format!("Tie at {}!", coup.player_total()),
+            side => format!(
+                "{} wins, {} to {}.",
+                side,
+                coup.player_total().max(coup.banker_total()),
+                coup.player_total().min(coup.banker_total())
+            ),
+        };
+        println!("{}", result.bold());
+
+        let mut net = 0;
+        for (side, stake) in bets {
+            let result = side.settle(stake, winner);
+            let line = format!("{} (${}): {}", side, stake, format_net(result));
+            match result {
+                n if n > 0 => println!("{}", line.green()),
+                n if n < 0 => println!("{}", line.red()),
+                _ => println!("{} (push)", line),
+            }
+            net += result;
+        }
+        balance = balance.saturating_add_signed(net);
+        println!("Net result this coup: {}", format_net(net));
+    }
+}
+
+/// Deals a coup, turning the cards over one step at a time.
+fn deal(shoe: &mut Shoe) -> Coup {
+    let coup = Coup::deal(shoe);
+    let show = |name: &str, cards: &[Card]| {
+        println!("{}: {} ({})", name, format_hand(cards), total(cards));
+    };
+    show("Player", &coup.player[..2]);
+    show("Banker", &coup.banker[..2]);
+    if coup.is_natural() {
+        println!("{}", "Natural!".yellow());
+    }
+    if coup.player.len() == 3 {
+        thread::sleep(Duration::from_secs(1));
+        println!("Player draws.");
+        show("Player", &coup.player);
+    }
+    if coup.banker.len() == 3 {
+        thread::sleep(Duration::from_secs(1));
+        println!("Banker draws.");
+        show("Banker", &coup.banker);
+    }
+    coup
+}
+
+/// Asks for the bets on the next coup until valid ones are given. Returns `None` when the
+/// user quits.
+fn ask_bets(balance: u32) -> Option<Vec<(Side, u32)>> {
+    loop {
+        println!("Place your bets, e.g. 'banker 10' or 'p 10 t 5' (or 'q' to quit)");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim() == "q" {
+            return None;
+        }
+        match parse_bets(&input) {
+            Ok(bets) if bets.iter().map(|&(_, stake)| stake).sum::<u32>() > balance => {
+                println!("Insufficient balance. Please bet less.");
+            }
+            Ok(bets) => return Some(bets),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// Reads bets given as pairs of a side and an amount, such as "b 10 t 5".
+fn parse_bets(input: &str) -> Result<Vec<(Side, u32)>, String> {
+    let mut parts = input.split_whitespace();
+    let mut bets: Vec<(Side, u32)> = Vec::new();
+    while let Some(name) = parts.next() {
+        let side: Side = name.parse()?;
+        let stake = parts
+            .next()
+            .and_then(|stake| stake.parse().ok())
+            .filter(|&stake| stake > 0)
+            .ok_or(format!("Please provide a valid amount for {}", side))?;
+        if bets.iter().any(|&(placed, _)| placed == side) {
+            return Err(format!("{} can only be bet once per coup", side));
+        }
+        bets.push((side, stake));
+    }
+    if bets.is_empty() {
+        return Err("Please place at least one bet".to_string());
+    }
+    Ok(bets)
+}
+
+/// Prints the bead plate and the big road side by side, with a tally of the shoe so far.
+fn print_scoreboard(scoreboard: &Scoreboard) {
+    let plate = scoreboard.bead_plate(SCOREBOARD_ROWS);
+    let road = scoreboard.big_road(SCOREBOARD_ROWS);
+    let tally = |side| {
+        scoreboard
+            .results()
+            .iter()
+            .filter(|&&result| result == side)
+            .count()
+    };
+    println!(
+        "Bead plate{}Big road    (Player {}, Banker {}, Tie {})",
+        " ".repeat(2 * plate[0].len().max(5) - 7),
+        tally(Side::Player),
+        tally(Side::Banker),
+        tally(Side::Tie)
+    );
+    for (plate_row, road_row) in plate.iter().zip(&road) {
+        let beads: String = plate_row.iter().map(|&bead| format_bead(bead)).collect();
+        let road: String = road_row
+            .iter()
+            .map(|&cell| format_road_cell(cell))
+            .collect();
+        let padding = " ".repeat(2 * (plate_row.len().max(5) - plate_row.len()));
+        println!("{}{} │ {}", beads, padding, road);
+    }
+}
+
+fn side_colour(side: Side, text: String) -> ColoredString {
+    match side {
+        Side::Player => text.blue().bold(),
+        Side::Banker => text.red().bold(),
+        Side::Tie => text.green().bold(),
+    }
+}
+
+/// A bead plate entry: the result's letter in its colour.
+fn format_bead(bead: Option<Side>) -> String {
+    match bead {
+        Some(side) => format!("{} ", side_colour(side, side.letter().to_string())),
+        None => "· ".to_string(),
+    }
+}
+
+/// A big road entry: the winner's ring in its colour, followed by the number of ties
+/// after it in green.
+fn format_road_cell(cell: Option<RoadCell>) -> String {
+    match cell {
+        Some(RoadCell { winner, ties: 0 }) => format!("{} ", side_colour(winner, "◯".into())),
+        Some(RoadCell { winner, ties }) => format!(
+            "{}{}",
+            side_colour(winner, "◯".into()),
+            ties.min(9).to_string().green()
+        ),
+        None => "· ".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bets() {
+        assert_eq!(
+            parse_bets("banker 10 t 5"),
+            Ok(vec![(Side::Banker, 10), (Side::Tie, 5)])
+        );
+        assert!(parse_bets("").is_err());
+        assert!(parse_bets("p").is_err());
+        assert!(parse_bets("p 0").is_err());
+        assert!(parse_bets("p 5 player 5").is_err());
+        assert!(parse_bets("dragon 5").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use colored::*;
+use games::blackjack::{simulate, Stats, StrategyKind, TableRules, BASE_BET};
+use games::cli::take_flag_value;
+use std::env;
+use std::process;
+use std::thread;
+use std::time::Instant;
+
+/// Settings for a simulation run, on top of the table rules.
+struct Options {
+    rounds: u64,
+    threads: usize,
+    strategy: StrategyKind,
+    /// Bankroll for the risk of ruin estimate, in base bets.
+    bankroll: f64,
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let parsed =
+        parse_options(&mut args).and_then(|options| Ok((options, TableRules::from_args(&args)?)));
+    let (options, rules) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!(
+                "Usage: blackjack-sim [--hands <rounds>] [--threads <n>] \
+                 [--strategy basic|counting[:spread]|never-bust|mimic-dealer|reckless] \
+                 [--bankroll <base bets>] [--config <rules file>] [--<rule> <value> ...]"
+            );
+            eprintln!("e.g.   blackjack-sim --hands 10000000 --strategy counting:12 --decks 2");
+            process::exit(1);
+        }
+    };
+
+    println!("Table rules: {}", rules);
+    println!(
+        "Simulating {} rounds of {:?} play on {} threads...",
+        options.rounds, options.strategy, options.threads
+    );
+    let started = Instant::now();
+    let stats = run(&rules, &options);
+    let elapsed = started.elapsed().as_secs_f64();
+
+    println!("--------------------------------------------------------------");
+    println!(
+        "Rounds: {} ({} hands after splits) in {:.1}s",
+        stats.rounds, stats.hands, elapsed
+    );
+    println!(
+        "Average bet: {:.2} base bets of ${}",
+        stats.wagered / stats.rounds as f64,
+        BASE_BET
+    );
+    let ev = stats.ev_per_round();
+    let ev_line = format!(
+        "EV per round: {:+.4} base bets (± {:.4})",
+        ev,
+        1.96 * stats.standard_error()
+    );
+    if ev >= 0.0 {
+        println!("{}", ev_line.green());
+    } else {
+        println!("{}", ev_line.red());
+    }
+    println!(
+        "EV per dollar bet: {:+.3}% (house edge {:.3}%)",
+        100.0 * stats.ev_per_bet(),
+        -100.0 * stats.ev_per_bet()
+    );
+    println!(
+        "Variance per round: {:.3} (standard deviation {:.3} base bets)",
+        stats.variance(),
+        stats.variance().sqrt()
+    );
+    println!(
+        "Risk of ruin with a {} base bet bankroll: {:.2}%",
+        options.bankroll,
+        100.0 * stats.risk_of_ruin(options.bankroll)
+    );
+}
+
+/// Splits the rounds across the threads, each with its own shoe, and adds up the results.
+fn run(rules: &TableRules, options: &Options) -> Stats {
+    let threads = options.threads as u64;
+    thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|i| {
+                // The first threads take one extra round each when the split is uneven
+                let rounds = options.rounds / threads + u64::from(i < options.rounds % threads);
+                scope.spawn(move || simulate(rules, options.strategy, rounds))
+            })
+            .collect();
+        let mut total = Stats::default();
+        for worker in workers {
+            total.merge(&worker.join().expect("simulation thread panicked"));
+        }
+        total
+    })
+}
+
+/// Takes the simulator's own flags out of `args`, leaving the table rule flags behind.
+fn parse_options(args: &mut Vec<String>) -> Result<Options, String> {
+    let mut options = Options {
+        rounds: 1_000_000,
+        threads: thread::available_parallelism().map_or(1, |n| n.get()),
+        strategy: StrategyKind::Basic,
+        bankroll: 100.0,
+    };
+    if let Some(value) = take_flag_value(args, "--hands")? {
+        options.rounds = match value.replace('_', "").parse() {
+            Ok(rounds) if rounds > 0 => rounds,
+            _ => return Err(format!("invalid --hands '{}'", value)),
+        };
+    }
+    if let Some(value) = take_flag_value(args, "--threads")? {
+        options.threads = match value.parse() {
+            Ok(threads) if threads > 0 => threads,
+            _ => return Err(format!("invalid --threads '{}'", value)),
+        };
+    }
+    if let Some(value) = take_flag_value(args, "--strategy")? {
+        options.strategy = value.parse()?;
+    }
+    if let Some(value) = take_flag_value(args, "--bankroll")? {
+        options.bankroll = match value.parse() {
+            Ok(bankroll) if bankroll > 0.0 => bankroll,
+            _ => return Err(format!("invalid --bankroll '{}'", value)),
+        };
+    }
+    Ok(options)
+}
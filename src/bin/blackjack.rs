@@ -1,12 +1,84 @@
 use colored::*;
+use games::blackjack::{
+    action_evs, basic_strategy, insurance_offered, max_insurance, play_hands, settle,
+    settle_insurance, should_switch, spot_hands, switch_cards, switch_hands, Action,
+    CountingSystem, Hand, HandEvent, HoleCard, InsuranceResult, Outcome, PlayerHand, SideBet,
+    Strategy, StrategyKind, Surrender, TableRules, HI_LO,
+};
+use games::cards::{create_deck, format_card, format_hand, load_deck, Card, Rank, Shoe};
+use games::cli::{format_net, take_flag_value};
 use rand::seq::SliceRandom;
-use rand::thread_rng;
-use std::io;
+use rand::{thread_rng, Rng};
+use std::env;
+use std::io::{self, Write};
+use std::process;
 use std::thread;
 use std::time::Duration;
 
 fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let mut coach = Coach {
+        enabled: args.iter().any(|arg| arg == "--coach"),
+        decisions: 0,
+        correct: 0,
+    };
+    args.retain(|arg| arg != "--coach");
+    let deck_file = take_flag_value(&mut args, "--deck-file").and_then(|path| match path {
+        Some(path) => load_deck(&path).map(Some),
+        None => Ok(None),
+    });
+    let parsed = deck_file.and_then(|deck| {
+        let seats = take_flag_value(&mut args, "--seats")?;
+        let seats = parse_seats(seats.as_deref().unwrap_or("you"))?;
+        let trainer = Trainer::from_args(&mut args)?;
+        Ok((deck, seats, trainer, TableRules::from_args(&args)?))
+    });
+    let (deck, mut seats, mut trainer, rules) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!(
+                "Usage: blackjack [--coach] [--trainer [hi-lo|ko|omega-ii|zen]] [--quiz-every <rounds>] \
+                 [--drill-speed <ms>] [--deck-file <cards file>] [--seats <you,basic,reckless,counter,...>] \
+                 [--config <rules file>] [--<rule> <value> ...]"
+            );
+            eprintln!("e.g.   blackjack --decks 2 --penetration 65 --dealer-hits-soft-17 true --blackjack-pays 6:5");
+            eprintln!("       blackjack --seats basic,you,reckless,counter");
+            eprintln!("       blackjack --variant spanish21 --decks 8");
+            eprintln!("       blackjack --variant switch");
+            process::exit(1);
+        }
+    };
+
     println!("Welcome to Blackjack!");
+    println!("Table rules: {}", rules);
+    println!(
+        "The shoe is reshuffled after {}% of the cards are dealt.",
+        (rules.penetration * 100.0).round()
+    );
+    if coach.enabled {
+        println!("Coach mode is on: every decision is checked against basic strategy.");
+    }
+    if seats.len() > 1 {
+        let names: Vec<String> = seats.iter().map(Seat::name).collect();
+        println!("Seats from the dealer's left: {}", names.join(", "));
+    }
+    if trainer.enabled {
+        println!(
+            "Counting trainer is on ({}): you will be quizzed on the count every {} rounds.",
+            trainer.system.name, trainer.quiz_every
+        );
+        println!("Type 'count' at the bet prompt to see the count, or 'drill' for a flash drill.");
+    }
+    // A deck file is dealt in its exact order, once, before play goes on with a normal shoe
+    let mut scripted = deck.is_some();
+    let mut shoe = match deck {
+        Some(cards) => {
+            println!("Dealing {} cards from the deck file first.", cards.len());
+            Shoe::from_cards(cards, rules.shoe())
+        }
+        None => rules.shoe(),
+    };
     let mut balance = 100;
     loop {
         println!("--------------------------------------------------------------");
@@ -20,370 +92,1180 @@ fn main() {
             break;
         }
 
-        println!("Place your bet (or 'q' to quit)");
+        if scripted && shoe.cut_card_reached() {
+            println!(
+                "{}",
+                "The deck file has been dealt, continuing with a shuffled shoe.".yellow()
+            );
+            shoe = rules.shoe();
+            scripted = false;
+        } else if shoe.cut_card_reached() {
+            println!("{}", "Cut card reached, reshuffling the shoe.".yellow());
+            shoe.reshuffle();
+            if trainer.enabled {
+                println!("{}", "New shoe: the count starts over.".yellow());
+            }
+        }
+        print_shoe_status(&shoe);
+
+        let mut available = balance;
+        let mut quit = false;
+        for seat in seats.iter_mut().filter(|seat| seat.is_human()) {
+            let spots = rules.variant.spots() as u32;
+            let Some((bet, side_bets)) = ask_bet(seat, available, spots, &mut trainer, &shoe)
+            else {
+                quit = true;
+                break;
+            };
+            available -= bet * spots + side_bets.iter().map(|&(_, stake)| stake).sum::<u32>();
+            seat.hands = vec![PlayerHand::new(Hand::new(), bet)];
+            seat.side_bets = side_bets;
+        }
+        if quit {
+            break;
+        }
+
+        play_blackjack(&rules, &mut shoe, &mut coach, &mut balance, &mut seats);
+        trainer.finish_round(&shoe);
+    }
+    coach.print_summary();
+    trainer.print_summary();
+}
+
+/// Compares each decision with basic strategy while coach mode is on, and keeps score.
+struct Coach {
+    enabled: bool,
+    decisions: u32,
+    correct: u32,
+}
+
+impl Coach {
+    fn record(&mut self, taken: Action, advice: Action) {
+        if !self.enabled {
+            return;
+        }
+        self.decisions += 1;
+        if taken == advice {
+            self.correct += 1;
+        } else {
+            println!(
+                "{}",
+                format!("Coach: basic strategy says {} here, not {}.", advice, taken).yellow()
+            );
+        }
+    }
+
+    fn print_summary(&self) {
+        if !self.enabled || self.decisions == 0 {
+            return;
+        }
+        println!(
+            "Coach: {} of {} decisions matched basic strategy ({:.1}% accuracy).",
+            self.correct,
+            self.decisions,
+            100.0 * self.correct as f64 / self.decisions as f64
+        );
+    }
+}
+
+/// Card counting practice: keeps quizzing the player on the count of the shoe in play,
+/// and runs flash drills on a single deck.
+struct Trainer {
+    enabled: bool,
+    system: CountingSystem,
+    quiz_every: u32,
+    drill_speed: Duration,
+    rounds: u32,
+    quizzes: u32,
+    correct: u32,
+}
+
+impl Trainer {
+    /// Takes `--trainer [system]`, `--quiz-every <rounds>` and `--drill-speed <ms>` out of
+    /// `args`, leaving the table rule flags behind.
+    fn from_args(args: &mut Vec<String>) -> Result<Trainer, String> {
+        let mut trainer = Trainer {
+            enabled: false,
+            system: HI_LO,
+            quiz_every: 5,
+            drill_speed: Duration::from_millis(800),
+            rounds: 0,
+            quizzes: 0,
+            correct: 0,
+        };
+        if let Some(index) = args.iter().position(|arg| arg == "--trainer") {
+            args.remove(index);
+            trainer.enabled = true;
+            if index < args.len() && !args[index].starts_with("--") {
+                let name = args.remove(index);
+                trainer.system = CountingSystem::by_name(&name)
+                    .ok_or_else(|| format!("unknown counting system '{}'", name))?;
+            }
+        }
+        if let Some(value) = take_flag_value(args, "--quiz-every")? {
+            trainer.quiz_every = match value.parse() {
+                Ok(rounds) if rounds > 0 => rounds,
+                _ => return Err(format!("invalid --quiz-every '{}'", value)),
+            };
+        }
+        if let Some(value) = take_flag_value(args, "--drill-speed")? {
+            let millis = value
+                .parse()
+                .map_err(|_| format!("invalid --drill-speed '{}'", value))?;
+            trainer.drill_speed = Duration::from_millis(millis);
+        }
+        Ok(trainer)
+    }
+
+    /// The running count and, for balanced systems, the true count of the shoe.
+    fn counts(&self, shoe: &Shoe) -> (i32, f64) {
+        let running = self.system.running_count(shoe.decks(), shoe.dealt_cards());
+        (running, self.system.true_count(running, shoe.remaining()))
+    }
+
+    fn show_count(&self, shoe: &Shoe) {
+        let (running, true_count) = self.counts(shoe);
+        if self.system.balanced {
+            println!(
+                "{} running count: {}, true count: {:.1} ({} cards dealt, {:.1} decks left)",
+                self.system.name,
+                running,
+                true_count,
+                shoe.dealt(),
+                shoe.remaining() as f64 / 52.0
+            );
+        } else {
+            println!(
+                "{} running count: {} ({} cards dealt)",
+                self.system.name,
+                running,
+                shoe.dealt()
+            );
+        }
+    }
+
+    /// Called after every round, quizzes the player once every `quiz_every` rounds.
+    fn finish_round(&mut self, shoe: &Shoe) {
+        if !self.enabled {
+            return;
+        }
+        self.rounds += 1;
+        if !self.rounds.is_multiple_of(self.quiz_every) {
+            return;
+        }
+        println!("{}", "Count check! What is the running count?".cyan());
+        let (running, _) = self.counts(shoe);
+        self.quizzes += 1;
+        if ask_count() == Some(running) {
+            self.correct += 1;
+            println!("{}", "Correct!".green());
+        } else {
+            println!(
+                "{}",
+                format!("Not quite, the running count is {}.", running).red()
+            );
+        }
+        self.show_count(shoe);
+    }
+
+    /// Flashes a random number of cards from a shuffled deck, one at a time, and then asks
+    /// for their running count.
+    fn drill(&mut self) {
+        let mut deck = create_deck();
+        deck.shuffle(&mut thread_rng());
+        deck.truncate(thread_rng().gen_range(10..=52));
+        println!("Count the cards as they flash by. Press enter to start.");
+        let mut ready = String::new();
+        io::stdin()
+            .read_line(&mut ready)
+            .expect("Failed to read line");
+        for card in &deck {
+            print!("\r{:<30}", format_card(card));
+            io::stdout().flush().expect("Failed to flush stdout");
+            thread::sleep(self.drill_speed);
+        }
+        print!("\r{:<30}\r", "");
+        println!("{} cards. What is the running count?", deck.len());
+        // A single deck starts from the system's one deck initial count
+        let running = self.system.running_count(1, &deck);
+        self.quizzes += 1;
+        if ask_count() == Some(running) {
+            self.correct += 1;
+            println!("{}", "Correct!".green());
+        } else {
+            println!(
+                "{}",
+                format!("Not quite, the running count is {}.", running).red()
+            );
+        }
+    }
+
+    fn print_summary(&self) {
+        if !self.enabled || self.quizzes == 0 {
+            return;
+        }
+        println!(
+            "Trainer: {} of {} counts correct ({:.1}%).",
+            self.correct,
+            self.quizzes,
+            100.0 * self.correct as f64 / self.quizzes as f64
+        );
+    }
+}
+
+/// Reads the player's answer to a count quiz.
+fn ask_count() -> Option<i32> {
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("Failed to read line");
+    answer.trim().parse().ok()
+}
+
+/// The most seats at a blackjack table.
+const MAX_SEATS: usize = 7;
+
+/// Who plays a seat at the table.
+enum Occupant {
+    Human,
+    /// A bot playing its own strategy, with what it has won or lost since it sat down.
+    Bot {
+        kind: StrategyKind,
+        strategy: Box<dyn Strategy>,
+        winnings: i32,
+    },
+}
+
+/// A seat at the table and everything it has riding on the current round.
+struct Seat {
+    /// The seat number from the dealer's left, or `None` when the user plays alone.
+    number: Option<usize>,
+    occupant: Occupant,
+    hands: Vec<PlayerHand>,
+    side_bets: Vec<(SideBet, u32)>,
+    insurance: u32,
+    /// Set when the seat took even money, which ends its round before play starts.
+    paid: bool,
+}
+
+impl Seat {
+    fn is_human(&self) -> bool {
+        matches!(self.occupant, Occupant::Human)
+    }
+
+    /// "Seat 3 (basic bot)", or "You" for a player alone at the table.
+    fn name(&self) -> String {
+        match (&self.occupant, self.number) {
+            (Occupant::Human, None) => "You".to_string(),
+            (Occupant::Human, Some(number)) => format!("Seat {} (you)", number),
+            (Occupant::Bot { kind, .. }, number) => {
+                format!("Seat {} ({} bot)", number.unwrap_or(1), bot_name(*kind))
+            }
+        }
+    }
+
+    fn hand_label(&self, index: usize) -> String {
+        hand_label(self.number, index)
+    }
+
+    /// Put in front of the seat's messages when there is more than one seat.
+    fn prefix(&self) -> String {
+        match self.number {
+            None => String::new(),
+            Some(_) => format!("{}: ", self.name()),
+        }
+    }
+
+    /// Everything on the table for this seat: every hand's stake plus insurance.
+    fn committed(&self) -> u32 {
+        total_stake(&self.hands) + self.insurance
+    }
+
+    /// Whether any hand still needs the dealer to finish the round.
+    fn has_live_hand(&self) -> bool {
+        !self.paid
+            && self
+                .hands
+                .iter()
+                .any(|player| !player.surrendered && !player.hand.is_bust())
+    }
+
+    /// Applies a win or loss: to the shared balance for the user's seats, to the bot's
+    /// winnings otherwise.
+    fn pay(&mut self, balance: &mut u32, net: i32) {
+        match &mut self.occupant {
+            Occupant::Human => *balance = balance.saturating_add_signed(net),
+            Occupant::Bot { winnings, .. } => *winnings += net,
+        }
+    }
+}
+
+/// "Hand 2", with the seat number in front once there is more than one seat.
+fn hand_label(seat_number: Option<usize>, index: usize) -> String {
+    match seat_number {
+        None => format!("Hand {}", index + 1),
+        Some(number) => format!("Seat {} hand {}", number, index + 1),
+    }
+}
+
+fn bot_name(kind: StrategyKind) -> &'static str {
+    match kind {
+        StrategyKind::Basic => "basic",
+        StrategyKind::Counting(_) => "counter",
+        StrategyKind::NeverBust => "never bust",
+        StrategyKind::MimicDealer => "mimic the dealer",
+        StrategyKind::Reckless => "reckless",
+    }
+}
+
+/// Reads a `--seats` list such as "basic,you,counter", one entry per seat from the
+/// dealer's left: "you" for a seat of your own, otherwise a bot strategy.
+fn parse_seats(list: &str) -> Result<Vec<Seat>, String> {
+    let occupants = list
+        .split(',')
+        .map(|entry| match entry.trim().to_lowercase().as_str() {
+            "you" | "me" => Ok(Occupant::Human),
+            name => name.parse().map(|kind: StrategyKind| Occupant::Bot {
+                kind,
+                strategy: kind.build(),
+                winnings: 0,
+            }),
+        })
+        .collect::<Result<Vec<Occupant>, String>>()?;
+    if occupants.len() > MAX_SEATS {
+        return Err(format!("a table has at most {} seats", MAX_SEATS));
+    }
+    if !occupants
+        .iter()
+        .any(|occupant| matches!(occupant, Occupant::Human))
+    {
+        return Err("at least one seat must be 'you'".to_string());
+    }
+    let alone = occupants.len() == 1;
+    Ok(occupants
+        .into_iter()
+        .enumerate()
+        .map(|(index, occupant)| Seat {
+            number: if alone { None } else { Some(index + 1) },
+            occupant,
+            hands: Vec::new(),
+            side_bets: Vec::new(),
+            insurance: 0,
+            paid: false,
+        })
+        .collect())
+}
+
+/// Asks one of the user's seats for its bet until a valid one is given, to be placed on
+/// each of its `spots` hands. Returns `None` when the user quits.
+fn ask_bet(
+    seat: &Seat,
+    available: u32,
+    spots: u32,
+    trainer: &mut Trainer,
+    shoe: &Shoe,
+) -> Option<(u32, Vec<(SideBet, u32)>)> {
+    loop {
+        if spots > 1 {
+            println!(
+                "{}Place your bet on each of your {} hands (or 'q' to quit). Add side bets after it, e.g. '10 pp 5 21+3 5 ll 5'",
+                seat.prefix(),
+                spots
+            );
+        } else {
+            println!(
+                "{}Place your bet (or 'q' to quit). Add side bets after it, e.g. '10 pp 5 21+3 5 ll 5'",
+                seat.prefix()
+            );
+        }
         let mut bet_input = String::new();
         io::stdin()
             .read_line(&mut bet_input)
             .expect("Failed to read line");
         if bet_input.trim() == "q" {
-            break;
+            return None;
         }
-        let mut parts = bet_input.trim().split_whitespace();
-        let mut bet: u32;
-        let mut secret_mode = false;
-        if let Some(first_part) = parts.next() {
-            if first_part == "s" {
-                secret_mode = true;
-                if let Some(bet_str) = parts.next() {
-                    bet = bet_str.parse().expect("Invalid bet amount");
-                } else {
-                    println!("Please provide a valid bet amount after 's'");
-                    continue;
-                }
-            } else {
-                bet = first_part.parse().expect("Invalid bet amount");
-            }
-        } else {
-            println!("Please provide a valid bet amount");
+        if trainer.enabled && bet_input.trim() == "count" {
+            trainer.show_count(shoe);
             continue;
         }
-
-        if bet > balance {
-            println!("Insufficient balance. Please enter a lower bet.");
+        if trainer.enabled && bet_input.trim() == "drill" {
+            trainer.drill();
             continue;
         }
-
-        play_blackjack(&mut balance, &mut bet, secret_mode);
+        match parse_bets(&bet_input) {
+            Ok((bet, side_bets))
+                if bet * spots + side_bets.iter().map(|&(_, stake)| stake).sum::<u32>()
+                    > available =>
+            {
+                println!("Insufficient balance. Please enter a lower bet.");
+            }
+            Ok(bets) => return Some(bets),
+            Err(e) => println!("{}", e),
+        }
     }
 }
 
-fn play_blackjack(balance: &mut u32, bet: &mut u32, secret: bool) {
-    let mut deck = create_deck();
-    let mut rng = thread_rng();
-    deck.shuffle(&mut rng);
-    let mut player_hands: Vec<Vec<String>>;
-    let mut dealer_hand: Vec<String>;
-    if secret {
-        player_hands = vec![pick_cards(&mut deck, false)];
-        print!("Pick dealers hand");
-        dealer_hand = pick_cards(&mut deck, true);
-    } else {
-        player_hands = vec![vec![deck.pop().unwrap(), deck.pop().unwrap()]];
-        dealer_hand = vec![deck.pop().unwrap(), deck.pop().unwrap()];
+/// Plays one round for every seat at the table. The user's seats must already hold
+/// their bets; bots place theirs as the cards are dealt.
+fn play_blackjack(
+    rules: &TableRules,
+    shoe: &mut Shoe,
+    coach: &mut Coach,
+    balance: &mut u32,
+    seats: &mut [Seat],
+) {
+    for seat in seats.iter_mut() {
+        seat.paid = false;
+        seat.insurance = 0;
+        let bet = match &mut seat.occupant {
+            Occupant::Human => seat.hands[0].bet,
+            Occupant::Bot { strategy, .. } => strategy.bet(shoe),
+        };
+        seat.hands = (0..rules.variant.spots())
+            .map(|spot| PlayerHand {
+                spot,
+                ..PlayerHand::new(Hand::new(), bet)
+            })
+            .collect();
     }
+    // One card to each hand from the dealer's left, then the dealer, and round again
+    let mut dealer_hand = Hand::new();
+    for round in 0..2 {
+        for player in seats.iter_mut().flat_map(|seat| seat.hands.iter_mut()) {
+            player.hand.push(shoe.draw());
+        }
+        // With no hole card the dealer's second card is only drawn once the players are done
+        if round == 0 || rules.hole_card == HoleCard::American {
+            dealer_hand.push(shoe.draw());
+        }
+    }
+    let up_card = dealer_hand.cards()[0];
 
-    println!(
-        "{}{}",
-        "\nDealer shows: ".yellow(),
-        format_card(&dealer_hand[0])
-    );
+    println!("{}{}", "\nDealer shows: ".yellow(), format_card(&up_card));
     println!(
         "{}{}",
         "Dealer score: ".yellow(),
-        hand_value(&dealer_hand[..1]).to_string().yellow() // Pass only the first card
+        Hand::from_cards(&[up_card]).value().to_string().yellow()
     );
+    if seats.len() > 1 {
+        for seat in seats.iter() {
+            let bets: Vec<String> = seat.hands.iter().map(|p| format!("${}", p.bet)).collect();
+            let hands: Vec<String> = seat
+                .hands
+                .iter()
+                .map(|p| format!("{} ({})", format_hand(p.hand.cards()), p.hand.value()))
+                .collect();
+            println!(
+                "{} bets {}: {}",
+                seat.name(),
+                bets.join(" + "),
+                hands.join(" and ")
+            );
+        }
+    }
+
+    for seat in seats.iter_mut().filter(|seat| !seat.side_bets.is_empty()) {
+        if seat.number.is_none() {
+            print_player_hand(&seat.hand_label(0), &seat.hands[0].hand);
+        }
+        let opening = [seat.hands[0].hand.cards()[0], seat.hands[0].hand.cards()[1]];
+        settle_side_bets(balance, seat, opening, up_card);
+    }
+
+    if insurance_offered(&up_card) {
+        for index in 0..seats.len() {
+            let committed = committed_by_others(seats, index);
+            let available = balance.saturating_sub(committed);
+            offer_insurance(&mut seats[index], balance, available);
+        }
+    }
+
+    if rules.surrender == Surrender::Early && rules.dealer_peeks(&up_card) {
+        for seat in seats.iter_mut().filter(|seat| !seat.paid) {
+            let label = seat.hand_label(0);
+            let prefix = seat.prefix();
+            let player = &mut seat.hands[0];
+            if player.hand.is_blackjack() {
+                continue;
+            }
+            player.surrendered = match &mut seat.occupant {
+                Occupant::Human => {
+                    print_player_hand(&label, &player.hand);
+                    println!(
+                        "{}Do you want to surrender before the dealer checks for blackjack? (y/n)",
+                        prefix
+                    );
+                    let mut answer = String::new();
+                    io::stdin()
+                        .read_line(&mut answer)
+                        .expect("Failed to read line");
+                    answer.trim().eq_ignore_ascii_case("y")
+                }
+                Occupant::Bot { strategy, .. } => {
+                    strategy.decide(&player.hand, up_card.rank, 1, rules) == Action::Surrender
+                }
+            };
+        }
+    }
+
+    if rules.dealer_peeks(&up_card) {
+        println!("Dealer checks for blackjack...");
+        let dealer_blackjack = dealer_hand.is_blackjack();
+        for seat in seats.iter_mut() {
+            resolve_insurance(balance, seat, dealer_blackjack);
+        }
+        if dealer_blackjack {
+            println!(
+                "{}{}",
+                "Dealer shows: ".yellow(),
+                format_hand(dealer_hand.cards())
+            );
+            for seat in seats.iter_mut() {
+                settle_round(rules, balance, seat, &dealer_hand);
+            }
+            return;
+        }
+        println!("Dealer does not have blackjack.");
+    }
+
+    for index in 0..seats.len() {
+        if seats[index].paid || seats[index].hands.iter().all(|p| p.surrendered) {
+            continue;
+        }
+        if seats[index].is_human() {
+            let available = balance.saturating_sub(committed_by_others(seats, index));
+            play_human_hands(
+                rules,
+                shoe,
+                coach,
+                available,
+                &mut seats[index],
+                &dealer_hand,
+            );
+        } else {
+            play_bot_hands(rules, shoe, &mut seats[index], up_card.rank);
+        }
+    }
+
+    if seats.iter().any(Seat::has_live_hand) {
+        dealer_turn(rules, shoe, &mut dealer_hand);
+    } else {
+        // The dealer has nothing to play for, but without a hole card the second card is
+        // still needed to settle insurance or a late surrender.
+        let late_surrender = rules.surrender == Surrender::Late
+            && seats.iter().any(|seat| seat.hands[0].surrendered);
+        let insured = seats.iter().any(|seat| seat.insurance > 0);
+        if dealer_hand.len() == 1 && (insured || late_surrender) {
+            dealer_hand.push(shoe.draw());
+        }
+        // Turn the hole card over anyway, so that every dealt card can be counted
+        println!(
+            "{}{}",
+            "Dealer's hand: ".yellow(),
+            format_hand(dealer_hand.cards())
+        );
+        let all_hands_busted = seats
+            .iter()
+            .filter(|seat| !seat.paid)
+            .all(|seat| seat.hands.iter().all(|player| player.hand.is_bust()));
+        if all_hands_busted && seats.len() == 1 {
+            println!("All hands busted. You lose!");
+        } else if all_hands_busted {
+            println!("All hands busted.");
+        }
+    }
 
+    for seat in seats.iter_mut() {
+        resolve_insurance(balance, seat, dealer_hand.is_blackjack());
+        settle_round(rules, balance, seat, &dealer_hand);
+    }
+}
+
+/// What the user's other seats have on the table, which `seats[index]` can't bet with.
+fn committed_by_others(seats: &[Seat], index: usize) -> u32 {
+    seats
+        .iter()
+        .enumerate()
+        .filter(|&(other, seat)| other != index && seat.is_human())
+        .map(|(_, seat)| seat.committed())
+        .sum()
+}
+
+/// Offers even money to a blackjack, or insurance to any other hand, against an Ace.
+fn offer_insurance(seat: &mut Seat, balance: &mut u32, available: u32) {
+    // Insurance covers every hand, but even money is only for a lone blackjack
+    let bet = total_stake(&seat.hands);
+    let blackjack = seat.hands.len() == 1 && seat.hands[0].hand.is_blackjack();
+    let prefix = seat.prefix();
+    let even_money = match &mut seat.occupant {
+        Occupant::Human if blackjack => {
+            println!("{}Dealer shows an Ace. Take even money? (y/n)", prefix);
+            let mut answer = String::new();
+            io::stdin()
+                .read_line(&mut answer)
+                .expect("Failed to read line");
+            answer.trim().eq_ignore_ascii_case("y")
+        }
+        Occupant::Human => {
+            seat.insurance = ask_insurance(max_insurance(bet).min(available.saturating_sub(bet)));
+            false
+        }
+        Occupant::Bot { strategy, .. } => {
+            let insure = strategy.take_insurance();
+            if insure && !blackjack {
+                println!("{}takes insurance.", prefix);
+                seat.insurance = max_insurance(bet);
+            }
+            insure && blackjack
+        }
+    };
+    if even_money {
+        let message = if seat.is_human() {
+            format!("{}You take even money and win ${}.", prefix, bet)
+        } else {
+            format!("{}takes even money and wins ${}.", prefix, bet)
+        };
+        println!("{}", message.green());
+        seat.pay(balance, bet as i32);
+        seat.paid = true;
+    }
+}
+
+/// Lets the user play each hand of their seat in turn. `available` is what the user can
+/// still put on this seat, for doubles and splits.
+fn play_human_hands(
+    rules: &TableRules,
+    shoe: &mut Shoe,
+    coach: &mut Coach,
+    available: u32,
+    seat: &mut Seat,
+    dealer_hand: &Hand,
+) {
+    let up_card = dealer_hand.cards()[0];
+    if seat.number.is_some() {
+        println!("{}", format!("\n{}, it's your turn.", seat.name()).blue());
+    }
+    if rules.variant.can_switch() && seat.hands.len() == 2 {
+        offer_switch(seat);
+    }
     let mut current_hand_index = 0;
-    let mut all_hands_busted = false;
-    while current_hand_index < player_hands.len() {
+    while current_hand_index < seat.hands.len() {
+        // A hand given up before play has nothing left to decide
+        if seat.hands[current_hand_index].surrendered {
+            current_hand_index += 1;
+            continue;
+        }
         let mut player_turn = true;
         while player_turn {
-            let value = hand_value(&player_hands[current_hand_index]);
-            if value == 21 {
-                match player_hands[current_hand_index].len() {
-                    2 => println!("{}", "Blackjack!\n".green()),
-                    _ => println!("{}", "You have 21!\n".green()),
+            let hand_count = spot_hands(&seat.hands, current_hand_index);
+            let label = seat.hand_label(current_hand_index);
+            let natural = seat.hands[current_hand_index].is_natural(hand_count);
+            let hand = &seat.hands[current_hand_index].hand;
+            if hand.value() == 21 {
+                if natural {
+                    println!("{}", "Blackjack!\n".green());
+                } else {
+                    println!("{}", "You have 21!\n".green());
                 }
                 player_turn = false;
                 current_hand_index += 1;
                 continue;
             }
-            print_player_hand(&player_hands[current_hand_index], current_hand_index);
-            println!("Do you want to (h)it, (s)tand, (d)ouble down, or (p)lit?\n");
+            let split_aces = hand_count > 1 && hand.cards()[0].rank == Rank::Ace;
+            if split_aces && !rules.hit_split_aces && !rules.can_split(hand, hand_count) {
+                print_player_hand(&label, hand);
+                println!("Split aces receive one card only.\n");
+                player_turn = false;
+                current_hand_index += 1;
+                continue;
+            }
+            let can_surrender = rules.can_surrender(hand, hand_count);
+            let advice = basic_strategy(hand, up_card.rank, hand_count, rules);
+            print_player_hand(&label, hand);
+            if can_surrender {
+                println!(
+                    "Do you want to (h)it, (s)tand, (d)ouble down, s(p)lit, or su(r)render? (? for a hint, ev for exact odds)\n"
+                );
+            } else {
+                println!(
+                    "Do you want to (h)it, (s)tand, (d)ouble down, or (p)lit? (? for a hint, ev for exact odds)\n"
+                );
+            }
             let mut action = String::new();
             io::stdin()
                 .read_line(&mut action)
                 .expect("Failed to read line");
+            // Money already on the table this round, which a double or split must not exceed
+            let committed = seat.committed();
+            let player = &mut seat.hands[current_hand_index];
             match action.trim() {
+                "?" => println!("{}", format!("Basic strategy: {}.", advice).cyan()),
+                "ev" => print_action_evs(rules, shoe, dealer_hand, &player.hand, hand_count),
                 "h" => {
-                    player_hands[current_hand_index].push(deck.pop().unwrap());
+                    coach.record(Action::Hit, advice);
+                    player.hand.push(shoe.draw());
                     println!("You hit.");
-                    if hand_value(&player_hands[current_hand_index]) > 21 {
-                        print_player_hand(&player_hands[current_hand_index], current_hand_index);
+                    if player.hand.is_bust() {
+                        print_player_hand(&label, &player.hand);
                         println!("{}", "Bust! You lose this hand.".red());
                         current_hand_index += 1;
                         player_turn = false;
-                        if player_hands.iter().all(|hand| hand_value(hand) > 21) {
-                            all_hands_busted = true;
-                        }
                     }
                 }
                 "s" => {
+                    coach.record(Action::Stand, advice);
                     println!("You stand.");
                     player_turn = false;
                     current_hand_index += 1;
                 }
                 "d" => {
-                    if *balance < *bet * 2 {
+                    if !rules.can_double(&player.hand, hand_count) {
+                        println!("You cannot double down on this hand.");
+                    } else if committed + player.bet > available {
                         println!("Insufficient balance to double down.");
                     } else {
-                        player_hands[current_hand_index].push(deck.pop().unwrap());
+                        coach.record(Action::Double, advice);
+                        player.doubled = true;
+                        player.hand.push(shoe.draw());
                         println!("You double down.");
-                        if hand_value(&player_hands[current_hand_index]) > 21 {
+                        print_player_hand(&label, &player.hand);
+                        if player.hand.is_bust() {
                             println!("{}", "Bust! You lose this hand.".red());
-                            println!("");
-                            *balance -= *bet * 2;
-                            current_hand_index += 1;
-                            player_turn = false;
-                            if player_hands.iter().all(|hand| hand_value(hand) > 21) {
-                                all_hands_busted = true;
-                            }
-                        } else {
-                            print_player_hand(&player_hands[current_hand_index], current_hand_index);
-                            println!("");
-                            player_turn = false;
-                            current_hand_index += 1;
-                            *bet *= 2;
+                        } else if rules.variant.double_down_rescue() && ask_rescue(player.bet) {
+                            println!("You rescue the double and give up your original bet.");
+                            player.surrendered = true;
                         }
+                        println!();
+                        player_turn = false;
+                        current_hand_index += 1;
                     }
                 }
                 "p" => {
-                    if player_hands.len() == 1 && can_split(&player_hands[0]) {
-                        let mut new_hand = vec![player_hands[0].pop().unwrap()];
-                        new_hand.push(deck.pop().unwrap());
-                        player_hands.push(new_hand);
-                        player_hands[0].push(deck.pop().unwrap());
+                    if !rules.can_split(&player.hand, hand_count) {
+                        println!("You cannot split your hand.");
+                    } else if committed + player.bet > available {
+                        println!("Insufficient balance to split.");
+                    } else {
+                        coach.record(Action::Split, advice);
+                        let mut new_hand = player.split();
+                        new_hand.hand.push(shoe.draw());
+                        player.hand.push(shoe.draw());
+                        seat.hands.insert(current_hand_index + 1, new_hand);
                         println!("You split your hand.");
+                    }
+                }
+                "r" => {
+                    if can_surrender {
+                        coach.record(Action::Surrender, advice);
+                        println!("You surrender.");
+                        player.surrendered = true;
+                        player_turn = false;
+                        current_hand_index += 1;
                     } else {
-                        println!("You cannot split your hand.");
+                        println!("You cannot surrender now.");
                     }
                 }
-                _ => println!("Invalid input. Please enter 'h' to hit, 's' to stand, 'd' to double down, or 'p' to split."),
+                _ => println!("Invalid input. Please enter 'h' to hit, 's' to stand, 'd' to double down, 'p' to split, 'r' to surrender, '?' for a hint, or 'ev' for exact odds."),
             }
         }
     }
+}
 
-    if all_hands_busted {
-        println!("All hands busted. You lose!");
-        *balance -= *bet * player_hands.len() as u32;
+/// Plays a bot's hands by its strategy, showing every move so the table can follow along
+/// (and count). Plays it can't make are taken as a hit.
+fn play_bot_hands(rules: &TableRules, shoe: &mut Shoe, seat: &mut Seat, up_card: Rank) {
+    let name = seat.name();
+    let Seat {
+        number,
+        occupant,
+        hands,
+        ..
+    } = seat;
+    let Occupant::Bot { strategy, .. } = occupant else {
         return;
-    }
-
-    let dealer_score = dealer_turn(&mut deck, &mut dealer_hand);
-
-    let mut player_wins = 0;
-    let mut player_loses = 0;
-    let mut player_ties = 0;
-    for hand in &player_hands {
-        let player_score = hand_value(hand);
-        println!("Your score: {}", player_score.to_string().blue());
-        if player_score == 21 && hand.len() == 2 && dealer_score != 21 {
-            println!("{}", "Blackjack! You win this hand!".green());
-            player_wins += 1;
-            *balance += (*bet * 3) / 2;
-        } else if dealer_score == 21 && dealer_hand.len() == 2 && player_score != 21 {
-            println!("{}", "Dealer has Blackjack. You lose this hand.".red());
-            player_loses += 1;
-        } else if player_score > 21 {
-            println!("{}", "Bust! You lose this hand.".red());
-            player_loses += 1;
-        } else if dealer_score > 21 || player_score > dealer_score {
-            println!("{}", "You win this hand!".green());
-            player_wins += 1;
-        } else if player_score == dealer_score {
-            println!("It's a tie!");
-            player_ties += 1;
-        } else {
-            println!("{}", "Dealer wins this hand".red());
-            player_loses += 1;
+    };
+    if let [first, second] = hands.as_mut_slice() {
+        if rules.variant.can_switch() && strategy.switch(&first.hand, &second.hand, up_card) {
+            switch_hands(first, second);
+            println!("{} switches.", name);
         }
     }
+    play_hands(
+        shoe,
+        rules,
+        strategy.as_mut(),
+        hands,
+        up_card,
+        &mut |event, index, player| {
+            let shown = format!(
+                "{}: {} ({})",
+                hand_label(*number, index),
+                format_hand(player.hand.cards()),
+                player.hand.value()
+            );
+            let busted = if player.hand.is_bust() { " Bust!" } else { "" };
+            match event {
+                HandEvent::Finished | HandEvent::Doubled => println!("{}{}", shown, busted.red()),
+                HandEvent::Chose(action) => {
+                    println!("{}, {}", shown, action.to_string().to_lowercase())
+                }
+            }
+        },
+    );
+}
 
-    if player_wins > 0 {
-        println!(
-            "{}{}",
-            player_wins.to_string().green(),
-            " hand(s) won!".green()
-        );
-        *balance += *bet * player_wins as u32;
+/// Settles every hand of a seat against the dealer, prints the result of each one and
+/// applies the net win or loss.
+fn settle_round(rules: &TableRules, balance: &mut u32, seat: &mut Seat, dealer_hand: &Hand) {
+    if seat.paid {
+        return;
     }
-    if player_loses > 0 {
-        println!(
-            "{}{}",
-            player_loses.to_string().red(),
-            " hand(s) lost.".red()
+    let results = settle(&seat.hands, dealer_hand, rules);
+    let mut net = 0;
+    for (index, (player, result)) in seat.hands.iter().zip(&results).enumerate() {
+        let message = match (seat.is_human(), result.outcome) {
+            (false, outcome) => format!("{}.", outcome),
+            (true, Outcome::Blackjack) => "Blackjack! You win this hand!".to_string(),
+            (true, Outcome::Bonus) => match rules.variant.bonus_21(&player.hand) {
+                Some((name, payout)) => format!("{} pays {}! You win this hand!", name, payout),
+                None => "You win this hand!".to_string(),
+            },
+            (true, Outcome::Win) => "You win this hand!".to_string(),
+            (true, Outcome::Push) => "It's a tie!".to_string(),
+            (true, Outcome::Lose) => "Dealer wins this hand.".to_string(),
+            (true, Outcome::Bust) => "Bust! You lose this hand.".to_string(),
+            (true, Outcome::DealerBlackjack) if result.net == 0 => {
+                "Dealer has Blackjack. Original bets only: this stake is returned.".to_string()
+            }
+            (true, Outcome::DealerBlackjack) => {
+                "Dealer has Blackjack. You lose this hand.".to_string()
+            }
+            (true, Outcome::Surrender) => "You surrendered half your bet.".to_string(),
+            (true, Outcome::Rescue) => "You rescued your double.".to_string(),
+        };
+        let line = format!(
+            "{} ({}, ${}{}): {} {}",
+            seat.hand_label(index),
+            player.hand.value(),
+            result.stake,
+            if player.doubled { " doubled" } else { "" },
+            message,
+            format_net(result.net)
         );
-        *balance -= *bet * player_loses as u32;
+        match result.net {
+            n if n > 0 => println!("{}", line.green()),
+            n if n < 0 => println!("{}", line.red()),
+            _ => println!("{}", line),
+        }
+        net += result.net;
     }
-    if player_ties > 0 {
-        println!("You tied {} hand(s).", player_ties);
+    seat.pay(balance, net);
+    match seat.occupant {
+        Occupant::Human => println!(
+            "{}Net result this round: {}",
+            seat.prefix(),
+            format_net(net).bold()
+        ),
+        Occupant::Bot { winnings, .. } => println!(
+            "{}{} this round, {} since sitting down",
+            seat.prefix(),
+            format_net(net),
+            format_net(winnings)
+        ),
     }
 }
 
-fn dealer_turn(deck: &mut Vec<String>, dealer_hand: &mut Vec<String>) -> i32 {
-    while hand_value(&dealer_hand) < 17 {
+/// Prints the exact expected value of every legal play on `hand`, worked out from the
+/// cards the player has not seen: the rest of the shoe and the dealer's hole card.
+fn print_action_evs(
+    rules: &TableRules,
+    shoe: &Shoe,
+    dealer_hand: &Hand,
+    hand: &Hand,
+    hand_count: usize,
+) {
+    let mut unseen = shoe.undealt().to_vec();
+    unseen.extend_from_slice(&dealer_hand.cards()[1..]);
+    let evs = action_evs(
+        hand,
+        dealer_hand.cards()[0].rank,
+        hand_count,
+        &unseen,
+        rules,
+    );
+    let best = evs
+        .iter()
+        .map(|&(_, ev)| ev)
+        .fold(f64::NEG_INFINITY, f64::max);
+    for (action, ev) in evs {
+        let line = format!("  {:<12} {:+.4} per unit bet", action.to_string(), ev);
+        if ev == best {
+            println!("{}", line.cyan());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// The total amount wagered on the player's hands, counting doubles.
+fn total_stake(player_hands: &[PlayerHand]) -> u32 {
+    player_hands.iter().map(PlayerHand::stake).sum()
+}
+
+fn dealer_turn(rules: &TableRules, shoe: &mut Shoe, dealer_hand: &mut Hand) {
+    while rules.dealer_should_hit(dealer_hand) {
         println!(
             "{}{}",
             "\nDealer shows: ".yellow(),
-            format_hand(&dealer_hand)
+            format_hand(dealer_hand.cards())
         );
         println!(
             "{}{}",
             "Dealer score: ".yellow(),
-            hand_value(&dealer_hand).to_string().yellow() // Pass only the first card
+            dealer_hand.value().to_string().yellow()
         );
         println!("Dealer is thinking...");
-        thread::sleep(Duration::from_secs(3)); // Delay for 2 seconds
-        dealer_hand.push(deck.pop().unwrap());
+        thread::sleep(Duration::from_secs(3)); // Delay for 3 seconds
+        dealer_hand.push(shoe.draw());
         println!("Dealer hits.");
-        println!("Dealer's hand: {}", format_hand(&dealer_hand));
+        println!("Dealer's hand: {}", format_hand(dealer_hand.cards()));
     }
 
-    let dealer_score = hand_value(&dealer_hand);
     println!(
         "{}{}",
         "\nDealer shows: ".yellow(),
-        format_hand(&dealer_hand)
+        format_hand(dealer_hand.cards())
+    );
+    println!(
+        "Dealer's score: {}",
+        dealer_hand.value().to_string().yellow()
     );
-    println!("Dealer's score: {}", dealer_score.to_string().yellow());
-    dealer_score
 }
 
-////////////////////////////////////////////////////////////////////////////////////////////////////
-///////////////////////////////////////// HELPER FUNCTIONS /////////////////////////////////////////
-////////////////////////////////////////////////////////////////////////////////////////////////////
-
-// TODO - Fails when wrong cards are chosen (it just continues, when it should ask again)
-fn pick_cards(deck: &mut Vec<String>, dealer: bool) -> Vec<String> {
-    let mut hand = Vec::new();
-    for (i, card) in deck.iter().enumerate() {
-        println!("{}: {}", i, card);
-    }
-    if dealer {
-        println!("\nPick dealers hand by choosing the index of the 1st card, pressing enter, and then choosing the index of the 2nd card.");
-    } else {
-        println!("\nPick your hand by choosing the index of the 1st card, pressing enter, and then choosing the index of the 2nd card.");
-    }
-    let mut picked_indices = Vec::new();
-    for _ in 0..2 {
-        let mut input = String::new();
-        io::stdin()
-            .read_line(&mut input)
-            .expect("Failed to read line");
-        let index = input.trim().parse::<usize>().unwrap();
-        if index >= deck.len() {
-            println!("Invalid index. Please try again.");
-            continue;
-        }
-        if picked_indices.contains(&index) {
-            println!("You've already picked that card. Please try again.");
-            continue;
+/// Reads a main bet optionally followed by side bets as name and amount pairs, e.g.
+/// "10 pp 5 ll 2".
+fn parse_bets(input: &str) -> Result<(u32, Vec<(SideBet, u32)>), String> {
+    let mut parts = input.split_whitespace();
+    let bet = parts
+        .next()
+        .and_then(|bet| bet.parse().ok())
+        .ok_or("Please provide a valid bet amount")?;
+    let mut side_bets: Vec<(SideBet, u32)> = Vec::new();
+    while let Some(name) = parts.next() {
+        let side_bet: SideBet = name.parse()?;
+        let stake = parts
+            .next()
+            .and_then(|stake| stake.parse().ok())
+            .filter(|&stake| stake > 0)
+            .ok_or(format!("Please provide a valid amount for {}", side_bet))?;
+        if side_bets.iter().any(|&(placed, _)| placed == side_bet) {
+            return Err(format!("{} can only be bet once per round", side_bet));
         }
-
-        picked_indices.push(index);
-    }
-    // Sort in descending order so that removing elements doesn't mess up the indices
-    picked_indices.sort_by(|a, b| b.cmp(a));
-    // clone cards into hand and remove them from deck
-    for index in picked_indices {
-        hand.push(deck[index].clone());
-        deck.remove(index);
+        side_bets.push((side_bet, stake));
     }
-
-    hand
-}
-
-fn format_hand(hand: &[String]) -> String {
-    hand.iter()
-        .map(|card| format_card(card)) // Assuming format_card returns ColoredString
-        .map(|colored_card| colored_card.to_string()) // Convert each ColoredString to String
-        .collect::<Vec<String>>() // Now collecting into Vec<String>
-        .join(", ")
+    Ok((bet, side_bets))
 }
 
-fn format_card(card: &str) -> ColoredString {
-    let suit = card.split_whitespace().last().unwrap();
-    let mut colored_card: ColoredString = card.to_owned().bold(); // DK - Made bold just to convert it
-
-    match suit {
-        "Hearts" | "Diamonds" => colored_card = colored_card.red(),
-        "Spades" | "Clubs" => colored_card = colored_card.black(),
-        _ => {}
+/// Settles each side bet on the opening cards, on its own line, before play goes on.
+fn settle_side_bets(balance: &mut u32, seat: &mut Seat, player: [Card; 2], up_card: Card) {
+    let prefix = seat.prefix();
+    for (side_bet, stake) in seat.side_bets.clone() {
+        let net = side_bet.settle(stake, player, up_card);
+        seat.pay(balance, net);
+        match side_bet.evaluate(player, up_card) {
+            Some((hand, pays)) => println!(
+                "{}",
+                format!(
+                    "{}{} (${}): {} pays {}:1! {}",
+                    prefix,
+                    side_bet,
+                    stake,
+                    hand,
+                    pays,
+                    format_net(net)
+                )
+                .green()
+            ),
+            None => println!(
+                "{}{} (${}): no win. {}",
+                prefix,
+                side_bet,
+                stake,
+                format_net(net)
+            ),
+        }
     }
-    colored_card
 }
 
-fn can_split(hand: &[String]) -> bool {
-    let rank = hand[0].split_whitespace().next().unwrap();
-    hand.iter()
-        .all(|card| card.split_whitespace().next().unwrap() == rank)
+/// Pays or collects a seat's insurance bet once it is known whether the dealer has
+/// blackjack.
+fn resolve_insurance(balance: &mut u32, seat: &mut Seat, dealer_blackjack: bool) {
+    if seat.insurance == 0 {
+        return;
+    }
+    let prefix = seat.prefix();
+    match settle_insurance(seat.insurance, dealer_blackjack) {
+        InsuranceResult::Won(winnings) => {
+            println!(
+                "{}",
+                format!("{}Insurance pays ${}.", prefix, winnings).green()
+            );
+            seat.pay(balance, i32::try_from(winnings).unwrap_or(i32::MAX));
+        }
+        InsuranceResult::Lost(stake) => {
+            let message = if seat.is_human() {
+                format!("{}You lose your ${} insurance.", prefix, stake)
+            } else {
+                format!("{}loses ${} insurance.", prefix, stake)
+            };
+            println!("{}", message.red());
+            seat.pay(balance, -i32::try_from(stake).unwrap_or(i32::MAX));
+        }
+    }
+    seat.insurance = 0;
 }
 
-fn create_deck() -> Vec<String> {
-    let suits = ["Hearts", "Diamonds", "Clubs", "Spades"];
-    let ranks = [
-        "2", "3", "4", "5", "6", "7", "8", "9", "10", "Jack", "Queen", "King", "Ace",
-    ];
-    let mut deck = Vec::new();
-    for &suit in &suits {
-        for &rank in &ranks {
-            deck.push(format!("{} of {}", rank, suit)); // Directly push owned String
+/// Asks how much insurance the player wants, up to `max`. An empty answer declines it.
+fn ask_insurance(max: u32) -> u32 {
+    if max == 0 {
+        return 0;
+    }
+    loop {
+        println!(
+            "Dealer shows an Ace. Insurance? Enter an amount up to ${} (or press enter to decline)",
+            max
+        );
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim().is_empty() {
+            return 0;
+        }
+        match input.trim().parse::<u32>() {
+            Ok(stake) if stake <= max => return stake,
+            _ => eprintln!(
+                "ERROR: Insurance must be a whole amount between 0 and {}",
+                max
+            ),
         }
     }
-    deck
 }
 
-fn hand_value(hand: &[String]) -> i32 {
-    let mut value = 0;
-    let mut has_ace = false;
-    for card in hand {
-        let rank = card.split_whitespace().next().unwrap();
-        let card_value = match rank {
-            "2" => 2,
-            "3" => 3,
-            "4" => 4,
-            "5" => 5,
-            "6" => 6,
-            "7" => 7,
-            "8" => 8,
-            "9" => 9,
-            "10" | "Jack" | "Queen" | "King" => 10,
-            "Ace" => {
-                has_ace = true;
-                11
+/// Shows both of the seat's hands and what switching their second cards would make, and
+/// swaps them if the user wants to.
+fn offer_switch(seat: &mut Seat) {
+    let (mut first, mut second) = (seat.hands[0].hand.clone(), seat.hands[1].hand.clone());
+    switch_cards(&mut first, &mut second);
+    print_player_hand(&seat.hand_label(0), &seat.hands[0].hand);
+    print_player_hand(&seat.hand_label(1), &seat.hands[1].hand);
+    println!(
+        "Switching the second cards makes {} ({}) and {} ({}).",
+        format_hand(first.cards()),
+        first.value(),
+        format_hand(second.cards()),
+        second.value()
+    );
+    loop {
+        println!("Do you want to switch? (y/n, ? for a hint)");
+        let mut answer = String::new();
+        io::stdin()
+            .read_line(&mut answer)
+            .expect("Failed to read line");
+        match answer.trim() {
+            "?" => {
+                let hint = if should_switch(&seat.hands[0].hand, &seat.hands[1].hand) {
+                    "Switch."
+                } else {
+                    "Keep the hands as dealt."
+                };
+                println!("{}", hint.cyan());
             }
-            _ => 0,
-        };
-        value += card_value;
-    }
-
-    if has_ace && value > 21 {
-        let mut new_value = value;
-        for card in hand {
-            let rank = card.split_whitespace().next().unwrap();
-            if rank == "Ace" {
-                new_value -= 10;
-                // println!("Ace counted as 1"); - DK - not useful right now, it prints a lot, and it can be inferred by the user
-                break;
+            answer if answer.eq_ignore_ascii_case("y") => {
+                if let [first, second] = seat.hands.as_mut_slice() {
+                    switch_hands(first, second);
+                }
+                println!("You switch.\n");
+                return;
             }
+            _ => return,
         }
-        new_value
-    } else {
-        value
     }
 }
 
-fn print_player_hand(hand: &[String], hand_index: usize) {
+/// Offers a double down rescue: the hand is given up for the original `bet` and the double
+/// comes back.
+fn ask_rescue(bet: u32) -> bool {
     println!(
-        "{}{}{}{}",
-        "Hand ".blue(),
-        (hand_index + 1).to_string().blue(),
-        ": ",
-        format_hand(&hand)
+        "Rescue the double? You give up your original ${} and take back the rest. (y/n)",
+        bet
     );
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .expect("Failed to read line");
+    answer.trim().eq_ignore_ascii_case("y")
+}
+
+fn print_shoe_status(shoe: &Shoe) {
+    println!(
+        "{}",
+        format!(
+            "Shoe: {} cards remaining, {} until the reshuffle.",
+            shoe.remaining(),
+            shoe.cards_before_cut()
+        )
+        .dimmed()
+    );
+}
+
+fn print_player_hand(label: &str, hand: &Hand) {
+    println!("{}: {}", label.blue(), format_hand(hand.cards()));
     println!(
         "{}{}",
         "Your Score: ".blue(),
-        hand_value(&hand).to_string().blue()
+        hand.value().to_string().blue()
     );
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bets_with_side_bets() {
+        assert_eq!(parse_bets("10\n"), Ok((10, vec![])));
+        assert_eq!(
+            parse_bets("10 pp 5 21+3 2"),
+            Ok((
+                10,
+                vec![(SideBet::PerfectPairs, 5), (SideBet::TwentyOnePlusThree, 2)]
+            ))
+        );
+        assert!(parse_bets("ten").is_err());
+        assert!(parse_bets("10 pp").is_err());
+        assert!(parse_bets("10 pp 0").is_err());
+        assert!(parse_bets("10 ll 5 ll 5").is_err());
+        assert!(parse_bets("10 keno 5").is_err());
+    }
+
+    #[test]
+    fn test_parse_seats() {
+        let seats = parse_seats("basic, you,Reckless,counter").unwrap();
+        let names: Vec<String> = seats.iter().map(Seat::name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "Seat 1 (basic bot)",
+                "Seat 2 (you)",
+                "Seat 3 (reckless bot)",
+                "Seat 4 (counter bot)"
+            ]
+        );
+        assert_eq!(parse_seats("you").unwrap()[0].number, None);
+        assert!(parse_seats("basic,reckless").is_err());
+        assert!(parse_seats("you,you,you,you,you,you,you,you").is_err());
+        assert!(parse_seats("you,shark").is_err());
+    }
+}
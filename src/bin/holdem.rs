@@ -0,0 +1,365 @@
+use colored::*;
+use games::cards::format_hand;
+use games::cli::{format_net, take_flag_value};
+use games::holdem::{Action, Bot, Player, Table, MAX_PLAYERS};
+use games::poker::best_hand;
+use rand::thread_rng;
+use std::env;
+use std::io;
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+/// Everyone sits down with the same stack as the other games' starting balance.
+const STARTING_STACK: u32 = 100;
+
+const BOT_NAMES: [&str; MAX_PLAYERS - 1] = ["Alice", "Bob", "Carol", "Dave", "Erin"];
+
+/// The aggression of each bot when none is given: a spread from cautious to wild.
+const DEFAULT_AGGRESSION: [f64; MAX_PLAYERS - 1] = [0.2, 0.8, 0.5, 0.35, 0.65];
+
+/// Your seat at the table.
+const YOU: usize = 0;
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let parsed = (|| {
+        let bots = match take_flag_value(&mut args, "--bots")? {
+            Some(bots) => bots
+                .parse::<usize>()
+                .ok()
+                .filter(|bots| (1..MAX_PLAYERS).contains(bots))
+                .ok_or(format!(
+                    "--bots must be 1 to {}, got '{}'",
+                    MAX_PLAYERS - 1,
+                    bots
+                ))?,
+            None => 3,
+        };
+        let aggression = match take_flag_value(&mut args, "--aggression")? {
+            Some(list) => parse_aggression(&list)?,
+            None => DEFAULT_AGGRESSION.to_vec(),
+        };
+        let blinds = match take_flag_value(&mut args, "--blinds")? {
+            Some(blinds) => parse_blinds(&blinds)?,
+            None => (1, 2),
+        };
+        if let Some(arg) = args.first() {
+            return Err(format!("unexpected argument '{}'", arg));
+        }
+        Ok((bots, aggression, blinds))
+    })();
+    let (bots, aggression, (small_blind, big_blind)) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!("Usage: holdem [--bots <1-5>] [--aggression <0-1>[,<0-1>...]] [--blinds <small>/<big>]");
+            eprintln!("e.g.   holdem --bots 2 --aggression 0.9,0.1 --blinds 2/4");
+            process::exit(1);
+        }
+    };
+
+    let mut players = vec![Player::new("You", STARTING_STACK, None)];
+    for (index, name) in BOT_NAMES.iter().take(bots).enumerate() {
+        let aggression = aggression[index % aggression.len()];
+        players.push(Player::new(name, STARTING_STACK, Some(Bot { aggression })));
+    }
+    let mut table = Table::new(players, small_blind, big_blind);
+
+    println!("Welcome to no-limit Texas Hold'em!");
+    println!(
+        "Blinds are ${}/${} and everyone starts with ${}.",
+        small_blind, big_blind, STARTING_STACK
+    );
+    loop {
+        println!("--------------------------------------------------------------");
+        let opponents: Vec<String> = table.players[1..]
+            .iter()
+            .map(|player| {
+                let style = describe_style(player.bot.map_or(0.0, |bot| bot.aggression));
+                format!("{} ({}) ${}", player.name, style, player.stack)
+            })
+            .collect();
+        println!("At the table: {}", opponents.join(", "));
+        let balance = table.players[YOU].stack;
+        println!(
+            "{} {}",
+            "Your balance: $".green(),
+            balance.to_string().green()
+        );
+        if balance == 0 {
+            println!("{}", "You're out of money. Game over!".red());
+            break;
+        }
+        if table.players_with_chips() == 1 {
+            println!("{}", "You've won every chip at the table!".green().bold());
+            break;
+        }
+        println!("Press Enter to deal the next hand (or 'q' to quit)");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim() == "q" {
+            break;
+        }
+
+        play_hand(&mut table);
+        let net = table.players[YOU].stack as i32 - balance as i32;
+        println!("Net result this hand: {}", format_net(net));
+    }
+}
+
+/// Plays one hand from the blinds to the showdown.
+fn play_hand(table: &mut Table) {
+    table.start_hand();
+    println!(
+        "{} {} the button. Blinds ${}/${}.",
+        table.players[table.button].name,
+        if table.button == YOU { "have" } else { "has" },
+        table.small_blind,
+        table.big_blind
+    );
+    println!(
+        "Your cards: {}",
+        format_hand(&table.players[YOU].hole).bold()
+    );
+    let mut rng = thread_rng();
+    loop {
+        while let Some(seat) = table.to_act() {
+            let action = match table.players[seat].bot {
+                Some(bot) => {
+                    thread::sleep(Duration::from_millis(600));
+                    let action = bot.decide(table, seat, &mut rng);
+                    println!("{}", describe_action(table, seat, action));
+                    action
+                }
+                None => ask_action(table, seat),
+            };
+            if let Err(e) = table.act(action) {
+                println!("{}", e);
+            }
+        }
+        if table.hand_over() {
+            break;
+        }
+        table.next_street();
+        println!(
+            "{}: {} (pot ${})",
+            table.street,
+            format_hand(&table.board),
+            table.pot_total()
+        );
+    }
+
+    if table.players_in_hand() > 1 {
+        println!("Showdown!");
+        for player in table.players.iter().filter(|player| player.in_hand()) {
+            let value = best_hand(&[&player.hole[..], &table.board].concat());
+            println!(
+                "{}: {} ({})",
+                player.name,
+                format_hand(&player.hole),
+                value.category
+            );
+        }
+    }
+    let mut results = table.settle();
+    let contested = results.len() > 1;
+    // Side pots won by the same players read better as one
+    results.dedup_by(|later, earlier| {
+        let same =
+            later.winners == earlier.winners && later.value.is_some() == earlier.value.is_some();
+        if same {
+            earlier.amount += later.amount;
+        }
+        same
+    });
+    for result in results {
+        let names: Vec<&str> = result
+            .winners
+            .iter()
+            .map(|&seat| table.players[seat].name.as_str())
+            .collect();
+        let line = match result.value {
+            Some(value) if names.len() > 1 => format!(
+                "{} split ${} ({}).",
+                names.join(" and "),
+                result.amount,
+                value.category
+            ),
+            Some(value) => format!(
+                "{} ${} ({}).",
+                wins(names[0]),
+                result.amount,
+                value.category
+            ),
+            None if contested => format!("${} is returned to {}.", result.amount, names[0]),
+            None => format!(
+                "Everyone else folds. {} ${}.",
+                wins(names[0]),
+                result.amount
+            ),
+        };
+        if result.winners.contains(&YOU) {
+            println!("{}", line.green().bold());
+        } else {
+            println!("{}", line);
+        }
+    }
+}
+
+/// Asks what to do until the input makes sense. The table checks that the action is
+/// allowed once it is played.
+fn ask_action(table: &Table, seat: usize) -> Action {
+    let player = &table.players[seat];
+    let to_call = table.to_call(seat);
+    loop {
+        if !table.can_raise(seat) {
+            println!(
+                "Pot ${}, your stack ${}, ${} to call. Call (c) or fold (f)?",
+                table.pot_total(),
+                player.stack,
+                to_call
+            );
+        } else if to_call == 0 {
+            println!(
+                "Pot ${}, your stack ${}. Check (k), bet (b <amount>), all in (a) or fold (f)?",
+                table.pot_total(),
+                player.stack
+            );
+        } else {
+            println!(
+                "Pot ${}, your stack ${}, ${} to call. Call (c), raise (r <total>), all in (a) or fold (f)?",
+                table.pot_total(),
+                player.stack,
+                to_call
+            );
+        }
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        match parse_action(&input) {
+            Ok(action) => return action,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// Reads an action such as "f", "check", "call", "r 20", "bet 10" or "all in". Bets and
+/// raises give the total to bet on this street.
+fn parse_action(input: &str) -> Result<Action, String> {
+    let input = input.trim().to_lowercase();
+    let mut parts = input.split_whitespace();
+    let action = match (parts.next(), parts.next()) {
+        (Some("f" | "fold"), None) => Action::Fold,
+        (Some("k" | "check"), None) => Action::Check,
+        (Some("c" | "call"), None) => Action::Call,
+        (Some("a" | "allin" | "all-in"), None) | (Some("all"), Some("in")) => Action::AllIn,
+        (Some("b" | "bet" | "r" | "raise"), Some(amount)) => amount
+            .trim_start_matches('$')
+            .parse()
+            .map(Action::RaiseTo)
+            .map_err(|_| format!("'{}' is not an amount", amount))?,
+        (Some("b" | "bet" | "r" | "raise"), None) => {
+            return Err("Please say how much, e.g. 'r 20'".to_string())
+        }
+        _ => return Err(format!("Unknown action '{}'", input)),
+    };
+    if parts.next().is_some() {
+        return Err(format!("Unknown action '{}'", input));
+    }
+    Ok(action)
+}
+
+/// What a bot's action looks like to the rest of the table, e.g. "Bob raises to $12".
+fn describe_action(table: &Table, seat: usize, action: Action) -> String {
+    let player = &table.players[seat];
+    let all_in = player.bet + player.stack;
+    let what = match action {
+        Action::Fold => "folds".to_string(),
+        Action::Check => "checks".to_string(),
+        Action::Call if table.to_call(seat) == 0 => "checks".to_string(),
+        Action::Call if table.to_call(seat) == player.stack => {
+            format!("calls ${} and is all in", player.stack)
+        }
+        Action::Call => format!("calls ${}", table.to_call(seat)),
+        Action::RaiseTo(total) if total == all_in => format!("goes all in for ${}", total),
+        Action::AllIn => format!("goes all in for ${}", all_in),
+        Action::RaiseTo(total) if table.current_bet() == 0 => format!("bets ${}", total),
+        Action::RaiseTo(total) => format!("raises to ${}", total),
+    };
+    format!("{} {}.", player.name, what)
+}
+
+/// "You win" or "Alice wins".
+fn wins(name: &str) -> String {
+    match name {
+        "You" => "You win".to_string(),
+        _ => format!("{} wins", name),
+    }
+}
+
+fn describe_style(aggression: f64) -> &'static str {
+    match aggression {
+        a if a < 0.34 => "cautious",
+        a if a < 0.67 => "steady",
+        _ => "aggressive",
+    }
+}
+
+/// Reads bot aggression levels between 0 and 1, separated by commas.
+fn parse_aggression(list: &str) -> Result<Vec<f64>, String> {
+    list.split(',')
+        .map(|level| {
+            level
+                .trim()
+                .parse::<f64>()
+                .ok()
+                .filter(|level| (0.0..=1.0).contains(level))
+                .ok_or(format!(
+                    "aggression must be between 0 and 1, got '{}'",
+                    level
+                ))
+        })
+        .collect()
+}
+
+/// Reads the blinds as "<small>/<big>", e.g. "1/2".
+fn parse_blinds(blinds: &str) -> Result<(u32, u32), String> {
+    let error = || format!("blinds must look like 1/2, got '{}'", blinds);
+    let (small, big) = blinds.split_once('/').ok_or_else(error)?;
+    let small: u32 = small.trim().parse().map_err(|_| error())?;
+    let big: u32 = big.trim().parse().map_err(|_| error())?;
+    if small == 0 || big < small || big > STARTING_STACK {
+        return Err(error());
+    }
+    Ok((small, big))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_action() {
+        assert_eq!(parse_action("f\n"), Ok(Action::Fold));
+        assert_eq!(parse_action("Check"), Ok(Action::Check));
+        assert_eq!(parse_action("all in"), Ok(Action::AllIn));
+        assert_eq!(parse_action("r 20"), Ok(Action::RaiseTo(20)));
+        assert_eq!(parse_action("bet $15"), Ok(Action::RaiseTo(15)));
+        assert!(parse_action("raise").is_err());
+        assert!(parse_action("call 5").is_err());
+        assert!(parse_action("shove").is_err());
+    }
+
+    #[test]
+    fn test_parse_options() {
+        assert_eq!(parse_blinds("2/4"), Ok((2, 4)));
+        assert!(parse_blinds("4/2").is_err());
+        assert!(parse_blinds("2").is_err());
+        assert_eq!(parse_aggression("0.1, 1"), Ok(vec![0.1, 1.0]));
+        assert!(parse_aggression("1.5").is_err());
+    }
+}
@@ -1,247 +1,398 @@
 use colored::*;
-use rand::Rng;
+use games::cli::take_flag_value;
+use games::roulette::{
+    net_change, parse_bet_argument, parse_bets, Bet, BetSlip, Colour, Outcome, ParseError, Pocket,
+    Wheel, ZeroRule,
+};
+use std::env;
 use std::io;
+use std::process;
 use std::thread;
 use std::time::Duration;
 
 // Constants for better readability
-const ZERO: &str = "0";
-const DOUBLE_ZERO: &str = "00";
-const THIRTY_SEVEN: &str = "37";
-const ZERO_OR_DOUBLE: usize = 0;
+const ZERO: usize = 0; // Any of the green zero pockets
 const RED: usize = 1;
 const BLACK: usize = 2;
 const EVEN: usize = 1;
 const ODD: usize = 2;
-const RED_NUMBERS: [usize; 18] = [
-    1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
-];
 
 /// The entry point of the roulette game application.
 /// Initializes player balance to $100 and runs the main game loop.
 /// The game loop continues until the player's balance is greater than 0.
 /// It handles betting input, choice of bet, and calculates win/loss.
 fn main() {
-    print_roulette_table();
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let parsed = (|| {
+        let wheel = match take_flag_value(&mut args, "--wheel")? {
+            Some(name) => name.parse()?,
+            None => Wheel::American,
+        };
+        let zero_rule: ZeroRule = match take_flag_value(&mut args, "--zero-rule")? {
+            Some(name) => name.parse()?,
+            None => ZeroRule::None,
+        };
+        if let Some(arg) = args.first() {
+            return Err(format!("unexpected argument '{}'", arg));
+        }
+        if !zero_rule.allowed_on(wheel) {
+            return Err(format!(
+                "the {} rule is only played on a european wheel",
+                zero_rule
+            ));
+        }
+        Ok((wheel, zero_rule))
+    })();
+    let (wheel, zero_rule) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!("Usage: roulette [--wheel <european|american|triple-zero>] [--zero-rule <none|la-partage|en-prison>]");
+            eprintln!("e.g.   roulette --wheel european --zero-rule en-prison");
+            process::exit(1);
+        }
+    };
+
+    print_roulette_table(wheel);
+    if zero_rule != ZeroRule::None {
+        println!(
+            "{}\n",
+            format!(
+                "Even-money bets are played {} when the ball lands on 0.",
+                zero_rule
+            )
+            .yellow()
+        );
+    }
     let mut balance: i32 = 100; // Player starts with $100
     println!(
         "{}{}.",
         "Welcome to the roulette table! To start you have $".green(),
         balance.to_string().green()
     );
+    let mut slip = BetSlip::new();
     while balance > 0 {
-        println!("How much would you like to bet?");
+        print_slip(wheel, &slip);
+        if slip.is_empty() {
+            println!(
+                "How much would you like to bet? (or type bets like \"10 red; 5 split 8-11\")"
+            );
+        } else {
+            println!("How much would you like to bet? (or type bets, or press enter to spin)");
+        }
         let mut bet_str = String::new();
         io::stdin()
             .read_line(&mut bet_str)
             .expect("Failed to read line");
-        let bet: i32 = bet_str.trim().parse().unwrap_or(0);
 
-        if bet > balance || bet <= 0 {
+        if bet_str.trim().is_empty() && !slip.is_empty() {
+            balance += play_round(wheel, zero_rule, &mut slip);
             println!(
-                "Invalid bet amount. You have ${}, and you tried to bet ${}.",
-                balance, bet
+                "{}{}.",
+                "You now have $".green(),
+                balance.to_string().green()
             );
+            if balance <= 0 {
+                println!("You've run out of money. Game over!");
+            }
+            continue;
+        }
+
+        // Money already on the slip cannot be bet again
+        let available = balance - slip.total();
+        // Anything but a plain amount is read as a line of bets, skipping the menus
+        let input = bet_str.trim();
+        if !input.is_empty() && input.parse::<i32>().is_err() {
+            add_bets(wheel, input, available, &mut slip);
             continue;
         }
 
-        println!("What would you like to bet on? \n - Color (c)\n - Parity (Even/Odd) (p)\n - 1-18/19-36 (h)\n - Dozen (d)\n - Column (co)\n - Number (n)");
+        let bet: i32 = input.parse().unwrap_or(0);
+        if bet > available || bet <= 0 {
+            println!(
+                "Invalid bet amount. You have ${} left to bet, and you tried to bet ${}.",
+                available, bet
+            );
+            continue;
+        }
+
+        println!("What would you like to bet on? \n - Color (c)\n - Parity (Even/Odd) (p)\n - 1-18/19-36 (h)\n - Dozen (d)\n - Column (co)\n - Number (n)\n - Split (s)\n - Street (st)\n - Corner (cr)\n - Six line (sl)\n - {} (b)", basket_name(wheel));
         let mut choice = String::new();
         io::stdin()
             .read_line(&mut choice)
             .expect("Failed to read line");
         choice = choice.trim().to_lowercase();
 
-        let win = match choice.as_str() {
-            "c" => play_color(),
-            "p" => play_parity(),
-            "h" => play_half(),
-            "d" => play_dozen(),
-            "co" => play_column(),
-            "n" => play_number(),
+        let chosen = match choice.as_str() {
+            "c" => bet_color(),
+            "p" => bet_parity(),
+            "h" => bet_half(),
+            "d" => bet_dozen(),
+            "co" => bet_column(),
+            "n" => bet_number(wheel),
+            "s" => bet_inside(
+                "Bet on two numbers next to each other on the table, e.g. 8-11",
+                |input| parse_bet_argument(wheel, "split", input),
+            ),
+            "st" => bet_inside(
+                "Bet on a row of three by its first number, e.g. 7 for 7, 8 and 9",
+                |input| parse_bet_argument(wheel, "street", input),
+            ),
+            "cr" => bet_inside(
+                "Bet on a block of four by its opposite corners, e.g. 17-21",
+                |input| parse_bet_argument(wheel, "corner", input),
+            ),
+            "sl" => bet_inside(
+                "Bet on two rows by the first number of the upper row, e.g. 7 for 7 to 12",
+                |input| parse_bet_argument(wheel, "line", input),
+            ),
+            "b" => Bet::Basket,
             _ => {
                 println!("Invalid choice, please enter one of the correct options.");
                 continue;
             }
         };
-
-        if win > 0 {
-            println!("You won ${} on a bet of ${}!\n\n", bet * win, bet);
-            balance += bet * win;
-        } else {
-            println!("You lost ${}.\n\n", bet);
-            balance -= bet;
-        }
-        println!(
-            "{}{}.",
-            "You now have $".green(),
-            balance.to_string().green()
-        );
-        if balance <= 0 {
-            println!("You've run out of money. Game over!");
-        }
+        add_to_slip(wheel, &mut slip, chosen, bet);
+        println!();
     }
 }
 
 /// Simulates spinning the roulette table and returns the result.
-/// Delays the simulation for 2 seconds to mimic the spinning and then generates
-/// a random number between 1 and 37 inclusive to represent the roulette result.
+/// Delays the simulation for 2 seconds to mimic the spinning and then picks
+/// one of the wheel's pockets, zeros included, each equally likely.
 ///
 /// Returns:
-/// A usize representing the slot number where the ball landed.
-fn spin_table() -> usize {
+/// The pocket where the ball landed.
+fn spin_table(wheel: Wheel) -> Pocket {
     println!("Spinning..........");
     thread::sleep(Duration::from_secs(2)); // Delay for 2 seconds
     let mut rng = rand::thread_rng();
-    rng.gen_range(1..=37)
-
-    // For testing purposes, we uncomment these
-    // return 37;
-    // return 0;
+    wheel.spin(&mut rng)
 }
 
-/// Executes a round of roulette based on the player's bet and checks if the player wins.
+/// Spins the wheel once and settles every wager on the slip against the result, printing
+/// how each one fared.
 ///
 /// Parameters:
-/// - `prompt`: The message displayed to the player indicating what to bet on.
-/// - `validate_guess`: A closure that validates and returns the player's guess.
-/// - `win_condition`: A closure that determines if the player's guess wins based on the result.
+/// - `wheel`: The wheel being spun.
+/// - `zero_rule`: What happens to even-money bets on a zero.
+/// - `slip`: The wagers on the table. Wagers sent to prison stay on it for the next spin.
 ///
 /// Returns:
-/// - `true` if the player wins the round.
-/// - `false` otherwise.
-fn play_roulette<G, W>(prompt: &str, validate_guess: G, win_condition: W) -> bool
-where
-    G: Fn() -> usize,
-    W: Fn(usize, usize) -> bool,
-{
-    println!("{}", prompt.yellow());
-    let guess = validate_guess();
-    let result = spin_table();
-    println!("Result: {}", colorize_number(result));
+/// - The change to the player's balance over all the wagers.
+fn play_round(wheel: Wheel, zero_rule: ZeroRule, slip: &mut BetSlip) -> i32 {
+    let result = spin_table(wheel);
+    print_result(wheel, result);
+
+    let settlements = slip.settle(wheel, zero_rule, result);
+    for settlement in &settlements {
+        let line = format!(
+            "  ${} on {}: {} ({:+})",
+            settlement.wager.amount, settlement.wager.bet, settlement.outcome, settlement.net
+        );
+        match settlement.outcome {
+            Outcome::Win => println!("{}", line.green()),
+            Outcome::Lose => println!("{}", line.red()),
+            _ => println!("{}", line.yellow()),
+        }
+    }
+
+    let net = net_change(&settlements);
+    if net > 0 {
+        println!(
+            "{}",
+            format!("YOU WIN! You are up ${} this spin.", net).green()
+        );
+    } else if net == 0 {
+        println!("You broke even this spin.");
+    } else {
+        println!("{}", format!("Sorry, you lost ${} this spin.", -net).red());
+    }
+    println!();
+    net
+}
+
+/// Prints where the ball landed along with its neighbours on the wheel.
+fn print_result(wheel: Wheel, result: Pocket) {
+    let neighbours: Vec<String> = wheel
+        .neighbours(result, 2)
+        .into_iter()
+        .map(|pocket| colorize_number(pocket).to_string())
+        .collect();
+    let description = match (get_color_num(result), get_parity(result)) {
+        (ZERO, _) => "green".to_string(),
+        (color, parity) => format!(
+            "{}, {}",
+            if color == RED { "red" } else { "black" },
+            if parity == EVEN { "even" } else { "odd" }
+        ),
+    };
+    println!(
+        "Result: {} {}   (on the wheel: {})",
+        colorize_number(result),
+        description,
+        neighbours.join(" ")
+    );
+}
 
-    if win_condition(guess, result) {
-        println!("{}", "YOU WIN!".green());
-        return true;
+/// Prints the wagers waiting on the slip for the next spin.
+fn print_slip(wheel: Wheel, slip: &BetSlip) {
+    if slip.is_empty() {
+        return;
     }
-    println!("{}", "Sorry, you lost.".red());
-    return false;
+    println!("{}", "Your bet slip:".yellow());
+    for wager in slip.wagers() {
+        let prison = if wager.imprisoned { " (en prison)" } else { "" };
+        println!(
+            "  ${} on {}{}",
+            wager.amount,
+            describe_bet(wheel, wager.bet),
+            prison
+        );
+    }
+    println!("  Total: ${}", slip.total());
 }
 
 /// Facilitates betting on the color outcome of the spin.
 ///
 /// Returns:
-/// - `1` if the player wins (the payout is 1:1).
-/// - `0` if the player loses.
-fn play_color() -> i32 {
-    let validate_guess = || get_valid_input(1, 2, false);
-    let win_condition = |guess, result| get_color_num(result) == guess;
-    let win = play_roulette("Bet on Red (1) or Black (2)", validate_guess, win_condition);
-    if win {
-        1
-    } else {
-        0
+/// - Red or black, each paying 1:1.
+fn bet_color() -> Bet {
+    println!("{}", "Bet on Red (1) or Black (2)".yellow());
+    match get_valid_input(1, 2) {
+        RED => Bet::Red,
+        _ => Bet::Black,
     }
 }
 
 /// Facilitates betting on the parity outcome of the spin.
 ///
 /// Returns:
-/// - `1` if the player wins (the payout is 1:1).
-/// - `0` if the player loses.
-fn play_parity() -> i32 {
-    let validate_guess = || get_valid_input(1, 2, false);
-    let win_condition = |guess, result| get_parity(result) == guess;
-    let win = play_roulette("Bet on Even (1) or Odd (2)", validate_guess, win_condition);
-    if win {
-        1
-    } else {
-        0
+/// - Even or odd, each paying 1:1.
+fn bet_parity() -> Bet {
+    println!("{}", "Bet on Even (1) or Odd (2)".yellow());
+    match get_valid_input(1, 2) {
+        EVEN => Bet::Even,
+        _ => Bet::Odd,
     }
 }
 
 /// Facilitates betting on the 1-18 or 19-36 outcome of the spin.
 ///
 /// Returns:
-/// - `1` if the player wins (the payout is 1:1).
-/// - `0` if the player loses.
-fn play_half() -> i32 {
-    let validate_guess = || get_valid_input(1, 2, false);
-    let win_condition = |guess, result| (guess == 1 && result <= 18) || (guess == 2 && result > 18);
-    let win = play_roulette(
-        "Bet on 1-18 (1) or 19-36 (2)",
-        validate_guess,
-        win_condition,
-    );
-    if win {
-        1
-    } else {
-        0
+/// - The low or high half, each paying 1:1.
+fn bet_half() -> Bet {
+    println!("{}", "Bet on 1-18 (1) or 19-36 (2)".yellow());
+    match get_valid_input(1, 2) {
+        1 => Bet::Low,
+        _ => Bet::High,
     }
 }
 
 /// Facilitates betting on the dozen outcome of the spin.
 ///
 /// Returns:
-/// - `2` if the player wins (the payout is 2:1).
-/// - `0` if the player loses.
-fn play_dozen() -> i32 {
-    let validate_guess = || get_valid_input(1, 3, false);
-    let win_condition = |guess, result| {
-        (guess == 1 && result <= 12)
-            || (guess == 2 && result > 12 && result <= 24)
-            || (guess == 3 && result > 24)
-    };
-    let win = play_roulette(
-        "Bet on 1-12 (1), 13-24 (2), or 25-36 (3)",
-        validate_guess,
-        win_condition,
-    );
-    if win {
-        2
-    } else {
-        0
-    }
+/// - One of the three dozens, paying 2:1.
+fn bet_dozen() -> Bet {
+    println!("{}", "Bet on 1-12 (1), 13-24 (2), or 25-36 (3)".yellow());
+    Bet::Dozen(get_valid_input(1, 3) as u8)
 }
 
 /// Facilitates betting on the column outcome of the spin.
 ///
 /// Returns:
-/// - `2` if the player wins (the payout is 2:1).
-/// - `0` if the player loses.
-fn play_column() -> i32 {
-    let validate_guess = || get_valid_input(1, 3, false);
-    let win_condition = |guess, result| {
-        (guess == 1 && result % 3 == 1)
-            || (guess == 2 && result % 3 == 2)
-            || (guess == 3 && result % 3 == 0)
-    };
-    let win = play_roulette(
-        "Bet on 1st (1), 2nd (2), or 3rd (3) column (see the ASCII art roulette table)",
-        validate_guess,
-        win_condition,
+/// - One of the three columns, paying 2:1.
+fn bet_column() -> Bet {
+    println!(
+        "{}",
+        "Bet on 1st (1), 2nd (2), or 3rd (3) column (see the ASCII art roulette table)".yellow()
     );
-    if win {
-        2
-    } else {
-        0
-    }
+    Bet::Column(get_valid_input(1, 3) as u8)
+}
+
+/// Facilitates the inside bets that cover a few neighbouring numbers on the table layout.
+///
+/// Parameters:
+/// - `prompt`: The message displayed to the player indicating what to bet on.
+/// - `parse`: Builds the bet from the player's input, or explains what is wrong with it.
+///
+/// Returns:
+/// - The bet, paying according to how many numbers it covers (e.g. 17:1 for a split).
+fn bet_inside<P>(prompt: &str, parse: P) -> Bet
+where
+    P: Fn(&str) -> Result<Bet, ParseError>,
+{
+    println!("{}", prompt.yellow());
+    get_valid_bet(parse)
 }
 
 /// Facilitates betting on a specific number outcome of the spin.
 ///
 /// Returns:
-/// - `35` if the player wins (the payout is 35:1).
-/// - `0` if the player loses.
-fn play_number() -> i32 {
-    let validate_guess = || get_valid_input(0, 37, true);
-    let win_condition = |guess: usize, result: usize| -> bool { guess == result };
-    let win = play_roulette("Bet on 0, 00, or 1 to 36", validate_guess, win_condition);
-    if win {
-        35
-    } else {
-        0
+/// - A straight up bet on the number, paying 35:1.
+fn bet_number(wheel: Wheel) -> Bet {
+    let zeros: Vec<String> = wheel.zeros().iter().map(|zero| zero.to_string()).collect();
+    println!(
+        "{}",
+        format!("Bet on {} or 1 to 36", zeros.join(", ")).yellow()
+    );
+    Bet::Straight(get_valid_pocket(wheel))
+}
+
+/// Adds a line of bets such as "10 red; 5 split 8-11" to the slip. Nothing is added if any
+/// of it is wrong, or if there is not enough money left for all of it.
+///
+/// Parameters:
+/// - `wheel`: The wheel in play, which decides which zeros can be bet on.
+/// - `input`: The player's line of bets.
+/// - `available`: The money not already on the slip.
+/// - `slip`: The slip to add the bets to.
+fn add_bets(wheel: Wheel, input: &str, available: i32, slip: &mut BetSlip) {
+    let wagers = match parse_bets(wheel, input) {
+        Ok(wagers) => wagers,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!("  {}", input);
+            eprintln!("  {}", e.underline(input).red());
+            return;
+        }
+    };
+    let total: i64 = wagers.iter().map(|&(_, amount)| i64::from(amount)).sum();
+    if total > i64::from(available) {
+        println!(
+            "Invalid bet amount. You have ${} left to bet, and you tried to bet ${}.",
+            available, total
+        );
+        return;
     }
+    for (bet, amount) in wagers {
+        add_to_slip(wheel, slip, bet, amount);
+    }
+    println!();
+}
+
+/// Puts `amount` on `bet` and confirms it to the player.
+fn add_to_slip(wheel: Wheel, slip: &mut BetSlip, bet: Bet, amount: i32) {
+    slip.add(bet, amount);
+    println!(
+        "Added ${} on {} at {}:1.",
+        amount,
+        describe_bet(wheel, bet),
+        bet.payout(wheel)
+    );
+}
+
+/// Names `bet` along with the pockets it covers when there are only a few of them.
+fn describe_bet(wheel: Wheel, bet: Bet) -> String {
+    let pockets = bet.pockets(wheel);
+    if bet.is_even_money() || pockets.len() == 12 || pockets.len() == 1 {
+        return bet.to_string();
+    }
+    let pockets: Vec<String> = pockets
+        .into_iter()
+        .map(|pocket| colorize_number(pocket).to_string())
+        .collect();
+    format!("{} ({})", bet, pockets.join(" "))
 }
 
 ////////////////////////////////////////////////////////////////////////////////////////////////////
@@ -253,21 +404,16 @@ fn play_number() -> i32 {
 /// Parameters:
 /// - `min`: The minimum acceptable value (inclusive).
 /// - `max`: The maximum acceptable value (inclusive).
-/// - `bet_00`: A flag indicating whether the special case of "00" is allowed.
 ///
 /// Returns:
 /// - A usize representing the user's validated input.
-fn get_valid_input(min: usize, max: usize, bet_00: bool) -> usize {
+fn get_valid_input(min: usize, max: usize) -> usize {
     loop {
         let mut guess = String::new();
         io::stdin()
             .read_line(&mut guess)
             .expect("Failed to read line");
 
-        if bet_00 && guess.trim() == DOUBLE_ZERO.to_string() {
-            guess = THIRTY_SEVEN.to_string(); // Treat 00 as 37
-        }
-
         let guess: usize = match guess.trim().parse() {
             Ok(num) => num,
             Err(_) => {
@@ -285,138 +431,148 @@ fn get_valid_input(min: usize, max: usize, bet_00: bool) -> usize {
     }
 }
 
-/// Determines the parity (even/odd) of a given number.
+/// Retrieves a pocket to bet on from the user, such as "17" or "00", that is on the wheel.
 ///
 /// Parameters:
-/// - `num`: The number to check the parity of.
+/// - `wheel`: The wheel in play, which decides which zeros can be bet on.
 ///
 /// Returns:
-/// - `ZERO_OR_DOUBLE` if the number is 0 or 00.
+/// - The pocket the user picked.
+fn get_valid_pocket(wheel: Wheel) -> Pocket {
+    loop {
+        let mut guess = String::new();
+        io::stdin()
+            .read_line(&mut guess)
+            .expect("Failed to read line");
+
+        match wheel.parse_pocket(&guess) {
+            Ok(pocket) => return pocket,
+            Err(e) => eprintln!("ERROR: {}", e),
+        }
+    }
+}
+
+/// Retrieves a bet from the user, prompting again until `parse` accepts the input.
+///
+/// Parameters:
+/// - `parse`: Builds the bet from the user's input, or explains what is wrong with it.
+///
+/// Returns:
+/// - The bet the user picked.
+fn get_valid_bet<P>(parse: P) -> Bet
+where
+    P: Fn(&str) -> Result<Bet, ParseError>,
+{
+    loop {
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+
+        match parse(input.trim()) {
+            Ok(bet) => return bet,
+            Err(e) => eprintln!("ERROR: {}", e),
+        }
+    }
+}
+
+/// What the bet on the zeros together with 1, 2 and 3 is called on `wheel`.
+fn basket_name(wheel: Wheel) -> &'static str {
+    match wheel {
+        Wheel::European => "First four",
+        Wheel::American | Wheel::TripleZero => "Top line",
+    }
+}
+
+/// Determines the parity (even/odd) of a given pocket.
+///
+/// Parameters:
+/// - `pocket`: The pocket to check the parity of.
+///
+/// Returns:
+/// - `ZERO` if the pocket is one of the zeros.
 /// - `EVEN` if the number is even.
 /// - `ODD` if the number is odd.
-fn get_parity(num: usize) -> usize {
-    if num == 0 || num == 37 {
-        return ZERO_OR_DOUBLE;
-    } else if num % 2 == 0 {
-        return EVEN;
-    } else if num % 2 == 1 {
-        return ODD;
-    } else {
-        panic!("Invalid number: {}", num); // panic because this should never be reached
+fn get_parity(pocket: Pocket) -> usize {
+    match pocket.number() {
+        None => ZERO,
+        Some(num) if num.is_multiple_of(2) => EVEN,
+        Some(_) => ODD,
     }
 }
 
-/// Determines the color of a given slot number.
+/// Determines the color of a given pocket.
 ///
 /// Parameters:
-/// - `num`: The slot number to check the color of.
+/// - `pocket`: The pocket to check the color of.
 ///
 /// Returns:
-/// - `ZERO_OR_DOUBLE` if the number is 0 or 00 (green).
-/// - `RED` if the number is associated with a red slot.
-/// - `BLACK` if the number is associated with a black slot.
-fn get_color_num(num: usize) -> usize {
-    if num == 0 || num == 37 {
-        return ZERO_OR_DOUBLE;
-    } else if RED_NUMBERS.contains(&num) {
-        return RED;
-    } else if num <= 37 {
-        return BLACK;
-    } else {
-        panic!("Invalid number: {}", num); // panic because this should never be reached
+/// - `ZERO` if the pocket is one of the zeros (green).
+/// - `RED` if the pocket is red.
+/// - `BLACK` if the pocket is black.
+fn get_color_num(pocket: Pocket) -> usize {
+    match pocket.colour() {
+        Colour::Green => ZERO,
+        Colour::Red => RED,
+        Colour::Black => BLACK,
     }
 }
 
-/// Applies coloring to the number based on its associated color in roulette.
+/// Applies coloring to the pocket based on its color in roulette.
 ///
 /// Parameters:
-/// - `num`: The roulette number to colorize.
+/// - `pocket`: The roulette pocket to colorize.
 ///
 /// Returns:
 /// - A `ColoredString` with the number colored according to its roulette color
-fn colorize_number(num: usize) -> ColoredString {
-    match num {
-        0 => ZERO.green(),
-        37 => DOUBLE_ZERO.green(), // Special handling for "00" to display it correctly.
-        _ if RED_NUMBERS.contains(&num) => format!("{}", num.to_string().red()).into(),
-        _ => format!("{}", num.to_string().black()).into(),
+fn colorize_number(pocket: Pocket) -> ColoredString {
+    colorize(pocket, pocket.to_string())
+}
+
+/// Colours `text` in the colour of `pocket`.
+fn colorize(pocket: Pocket, text: String) -> ColoredString {
+    match pocket.colour() {
+        Colour::Green => text.green(),
+        Colour::Red => text.red(),
+        Colour::Black => text.black(),
     }
 }
 
-/// Prints the ASCII art representation of a roulette table to the console.
-fn print_roulette_table() {
-    println!("\n  Welcome to the roulette table!\n");
-    println!(
-        "\t+-------+------+\n\
-         \t|   {}   |  {}  |\n\
-         \t+----+----+----+\n\
-         \t|  {} |  {} |  {} |\n\
-         \t+----+----+----+\n\
-         \t|  {} |  {} |  {} |\n\
-         \t+----+----+----+\n\
-         \t|  {} |  {} |  {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n\
-         \t| {} | {} | {} |\n\
-         \t+----+----+----+\n",
-        ZERO.green(),
-        DOUBLE_ZERO.green(),
-        colorize_number(1),
-        colorize_number(2),
-        colorize_number(3),
-        colorize_number(4),
-        colorize_number(5),
-        colorize_number(6),
-        colorize_number(7),
-        colorize_number(8),
-        colorize_number(9),
-        colorize_number(10),
-        colorize_number(11),
-        colorize_number(12),
-        colorize_number(13),
-        colorize_number(14),
-        colorize_number(15),
-        colorize_number(16),
-        colorize_number(17),
-        colorize_number(18),
-        colorize_number(19),
-        colorize_number(20),
-        colorize_number(21),
-        colorize_number(22),
-        colorize_number(23),
-        colorize_number(24),
-        colorize_number(25),
-        colorize_number(26),
-        colorize_number(27),
-        colorize_number(28),
-        colorize_number(29),
-        colorize_number(30),
-        colorize_number(31),
-        colorize_number(32),
-        colorize_number(33),
-        colorize_number(34),
-        colorize_number(35),
-        colorize_number(36)
-    );
+/// Prints the ASCII art representation of a roulette table to the console, with the
+/// wheel's zeros across the top.
+fn print_roulette_table(wheel: Wheel) {
+    println!("\n  Welcome to the {} roulette table!", wheel);
+    println!("  House edge: {:.2}%\n", wheel.house_edge() * 100.0);
+    // The zeros share the width of the three columns below them
+    let widths: &[usize] = match wheel.zeros().len() {
+        1 => &[14],
+        2 => &[7, 6],
+        _ => &[4, 4, 4],
+    };
+    let border = "\t+----+----+----+";
+    let zeros_border: Vec<String> = widths.iter().map(|&width| "-".repeat(width)).collect();
+    let zeros: Vec<String> = wheel
+        .zeros()
+        .iter()
+        .zip(widths)
+        .map(|(&zero, &width)| colorize(zero, format!("{:^width$}", zero)).to_string())
+        .collect();
+    println!("\t+{}+", zeros_border.join("+"));
+    println!("\t|{}|", zeros.join("|"));
+    println!("{}", border);
+    for row in 0..12 {
+        let cells: Vec<String> = (1..=3)
+            .map(|column| {
+                let pocket = Pocket::Number(row * 3 + column);
+                colorize(pocket, format!(" {:>2} ", pocket)).to_string()
+            })
+            .collect();
+        println!("\t|{}|", cells.join("|"));
+        println!("{}", border);
+    }
+    println!();
 }
 
-// TODO - Most tests hang on user input, or the 2 seconds delay in `spin_table`. Would need to refactor
-// the functions in order to handle testing better.
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -424,206 +580,35 @@ mod tests {
     /// Tests that `get_color_num` correctly identifies the color of a given number.
     #[test]
     fn test_get_color_num() {
-        assert_eq!(get_color_num(1), RED, "Number 1 should be red.");
-        assert_eq!(get_color_num(2), BLACK, "Number 2 should be black.");
         assert_eq!(
-            get_color_num(0),
-            ZERO_OR_DOUBLE,
-            "Number 0 should be green."
+            get_color_num(Pocket::Number(1)),
+            RED,
+            "Number 1 should be red."
+        );
+        assert_eq!(
+            get_color_num(Pocket::Number(2)),
+            BLACK,
+            "Number 2 should be black."
         );
+        assert_eq!(get_color_num(Pocket::Zero), ZERO, "0 should be green.");
         assert_eq!(
-            get_color_num(37),
-            ZERO_OR_DOUBLE,
-            "Number 37 (00) should be green."
+            get_color_num(Pocket::DoubleZero),
+            ZERO,
+            "00 should be green."
+        );
+        assert_eq!(
+            get_color_num(Pocket::TripleZero),
+            ZERO,
+            "000 should be green."
         );
         // Additional assertions can be added here for other specific numbers.
     }
 
-    // Stub for testing `spin_table` functionality.
-    // #[test]
-    // fn test_spin_table_range() {
-    //     for _ in 0..100 {
-    //         let result = spin_table();
-    //         assert!(
-    //             result >= 1 && result <= 37,
-    //             "Result out of range: {}",
-    //             result
-    //         );
-    //     }
-    // }
-
-    // // Stub for testing the `play_roulette` function's behavior.
-    // #[test]
-    // fn test_play_roulette_win_condition() {
-    //     // Implement this test to verify that `play_roulette` correctly identifies win conditions.
-    // }
-
-    // // Stub for testing the bet validation logic.
-    // #[test]
-    // fn test_bet_validation() {
-    //     // Implement this test to check if bets outside the valid range are correctly identified and handled.
-    // }
-
-    // // Stub for integration testing of the betting loop logic.
-    // #[test]
-    // fn test_game_betting_logic() {
-    //     // Implement this test to simulate a sequence of bets and verify the game's handling of win/loss and balance adjustment.
-    // }
-
-    // // Stub for testing correct handling of invalid input.
-    // #[test]
-    // fn test_invalid_input_handling() {
-    //     // Implement this test to ensure the game gracefully handles invalid input without crashing.
-    // }
-
-    // // Stub for testing payout calculations.
-    // #[test]
-    // fn test_payout_calculations() {
-    //     // This test should verify that payouts are calculated correctly for various bets.
-    // }
-
     #[test]
     fn test_get_parity() {
-        assert_eq!(get_parity(0), ZERO_OR_DOUBLE);
-        assert_eq!(get_parity(37), ZERO_OR_DOUBLE);
-        assert_eq!(get_parity(2), EVEN);
-        assert_eq!(get_parity(3), ODD);
+        assert_eq!(get_parity(Pocket::Zero), ZERO);
+        assert_eq!(get_parity(Pocket::DoubleZero), ZERO);
+        assert_eq!(get_parity(Pocket::Number(2)), EVEN);
+        assert_eq!(get_parity(Pocket::Number(3)), ODD);
     }
-
-    // #[test]
-    // fn test_colorize_number() {
-    //     // Stub
-    // }
-
-    // #[test]
-    // fn test_get_valid_input() {
-    //     // Stub
-    // }
-
-    // #[test]
-    // fn test_play_roulette() {
-    //     // Test win condition
-    //     let validate_guess = || 1;
-    //     let win_condition = |guess: usize, result: usize| guess == result;
-    //     assert!(play_roulette("Test win", validate_guess, win_condition));
-
-    //     // Test lose condition
-    //     let validate_guess = || 1;
-    //     let win_condition = |guess: usize, result: usize| guess != result;
-    //     assert!(!play_roulette("Test lose", validate_guess, win_condition));
-    // }
-
-    // #[test]
-    // fn test_play_color() {
-    //     // Test win scenario
-    //     let result = spin_table();
-    //     let expected_win = if get_color_num(result) == RED { 1 } else { 0 };
-    //     assert_eq!(play_color(), expected_win);
-
-    //     // Test lose scenario
-    //     let result = spin_table();
-    //     let expected_lose = if get_color_num(result) == BLACK { 0 } else { 1 };
-    //     assert_eq!(play_color(), expected_lose);
-    // }
-
-    // #[test]
-    // fn test_play_parity() {
-    //     // Test win scenario
-    //     let result = spin_table();
-    //     let expected_win = if get_parity(result) == EVEN { 1 } else { 0 };
-    //     assert_eq!(play_parity(), expected_win);
-
-    //     // Test lose scenario
-    //     let result = spin_table();
-    //     let expected_lose = if get_parity(result) == ODD { 0 } else { 1 };
-    //     assert_eq!(play_parity(), expected_lose);
-    // }
-
-    // #[test]
-    // fn test_play_half() {
-    //     // Test win scenario
-    //     let result = spin_table();
-    //     let expected_win = if (result <= 18 && 1 == 1) || (result > 18 && 2 == 2) {
-    //         1
-    //     } else {
-    //         0
-    //     };
-    //     assert_eq!(play_half(), expected_win);
-
-    //     // Test lose scenario
-    //     let result = spin_table();
-    //     let expected_lose = if (result <= 18 && 1 == 2) || (result > 18 && 2 == 1) {
-    //         0
-    //     } else {
-    //         1
-    //     };
-    //     assert_eq!(play_half(), expected_lose);
-    // }
-
-    // #[test]
-    // fn test_play_dozen() {
-    //     // Test win scenario
-    //     let result = spin_table();
-    //     let expected_win = if (result <= 12 && 1 == 1)
-    //         || (result > 12 && result <= 24 && 2 == 2)
-    //         || (result > 24 && 3 == 3)
-    //     {
-    //         2
-    //     } else {
-    //         0
-    //     };
-    //     assert_eq!(play_dozen(), expected_win);
-
-    //     // Test lose scenario
-    //     let result = spin_table();
-    //     let expected_lose = if (result <= 12 && 1 == 2)
-    //         || (result > 12 && result <= 24 && 2 == 3)
-    //         || (result > 24 && 3 == 1)
-    //     {
-    //         0
-    //     } else {
-    //         2
-    //     };
-    //     assert_eq!(play_dozen(), expected_lose);
-    // }
-
-    // #[test]
-    // fn test_play_column() {
-    //     // Test win scenario
-    //     let result = spin_table();
-    //     let expected_win = if (result % 3 == 1 && 1 == 1)
-    //         || (result % 3 == 2 && 2 == 2)
-    //         || (result % 3 == 0 && 3 == 3)
-    //     {
-    //         2
-    //     } else {
-    //         0
-    //     };
-    //     assert_eq!(play_column(), expected_win);
-
-    //     // Test lose scenario
-    //     let result = spin_table();
-    //     let expected_lose = if (result % 3 == 1 && 1 == 2)
-    //         || (result % 3 == 2 && 2 == 3)
-    //         || (result % 3 == 0 && 3 == 1)
-    //     {
-    //         0
-    //     } else {
-    //         2
-    //     };
-    //     assert_eq!(play_column(), expected_lose);
-    // }
-
-    // #[test]
-    // fn test_play_number() {
-    //     // Test win scenario
-    //     let result = spin_table();
-    //     let expected_win = if result == result { 35 } else { 0 };
-    //     assert_eq!(play_number(), expected_win);
-
-    //     // Test lose scenario
-    //     let result = spin_table();
-    //     let expected_lose = if result != result { 35 } else { 0 };
-    //     assert_eq!(play_number(), expected_lose);
-    // }
 }
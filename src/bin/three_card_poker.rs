@@ -0,0 +1,158 @@
+use colored::*;
+use games::cards::{format_hand, Card, Shoe};
+use games::cli::format_net;
+use games::poker::evaluate_three_card;
+use games::three_card_poker::{dealer_qualifies, settle, should_play, ANTE_BONUS, PAIR_PLUS};
+use std::io;
+use std::thread;
+use std::time::Duration;
+
+fn main() {
+    println!("Welcome to Three Card Poker!");
+    println!("Dealer plays with Queen high or better. Straights beat flushes.");
+    println!("Ante Bonus: {}", format_paytable(&ANTE_BONUS));
+    println!("Pair Plus: {}", format_paytable(&PAIR_PLUS));
+    let mut shoe = Shoe::new(1, 1.0);
+    let mut balance: u32 = 100;
+    loop {
+        println!("--------------------------------------------------------------");
+        println!(
+            "{} {}",
+            "Your balance: $".green(),
+            balance.to_string().green()
+        );
+        if balance == 0 {
+            println!("{}", "You're out of money. Game over!".red());
+            break;
+        }
+        let Some((ante, pair_plus)) = ask_wagers(balance) else {
+            break;
+        };
+
+        shoe.reshuffle();
+        let player: Vec<Card> = (0..3).map(|_| shoe.draw()).collect();
+        let dealer: Vec<Card> = (0..3).map(|_| shoe.draw()).collect();
+        let player_value = evaluate_three_card(&player);
+        println!(
+            "Your hand: {} ({})",
+            format_hand(&player),
+            player_value.category
+        );
+        let played = ask_play(ante, should_play(&player_value));
+
+        thread::sleep(Duration::from_secs(1));
+        let dealer_value = evaluate_three_card(&dealer);
+        println!(
+            "Dealer's hand: {} ({})",
+            format_hand(&dealer),
+            dealer_value.category
+        );
+        if played && !dealer_qualifies(&dealer_value) {
+            println!("{}", "Dealer doesn't qualify.".yellow());
+        }
+
+        let settlement = settle(&player, &dealer, ante, pair_plus, played);
+        let mut lines = vec![("Ante", ante, settlement.ante)];
+        if played {
+            lines.push(("Play", ante, settlement.play));
+            if settlement.ante_bonus > 0 {
+                lines.push(("Ante Bonus", ante, settlement.ante_bonus));
+            }
+        }
+        if pair_plus > 0 {
+            lines.push(("Pair Plus", pair_plus, settlement.pair_plus));
+        }
+        for (name, stake, result) in lines {
+            let line = format!("{} (${}): {}", name, stake, format_net(result));
+            match result {
+                n if n > 0 => println!("{}", line.green()),
+                n if n < 0 => println!("{}", line.red()),
+                _ => println!("{} (push)", line),
+            }
+        }
+        balance = balance.saturating_add_signed(settlement.total());
+        println!("Net result this hand: {}", format_net(settlement.total()));
+    }
+}
+
+/// Asks for the ante and an optional Pair Plus bet until valid ones are given. The
+/// balance has to cover the play bet as well. Returns `None` when the user quits.
+fn ask_wagers(balance: u32) -> Option<(u32, u32)> {
+    loop {
+        println!("Place your ante, optionally followed by a Pair Plus bet, e.g. '10' or '10 5' (or 'q' to quit)");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim() == "q" {
+            return None;
+        }
+        match parse_wagers(&input) {
+            Ok((ante, pair_plus)) if ante.saturating_mul(2).saturating_add(pair_plus) > balance => {
+                println!(
+                    "Insufficient balance: you need to cover the ante, the play bet and Pair Plus."
+                )
+            }
+            Ok(wagers) => return Some(wagers),
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// Reads an ante and an optional Pair Plus bet, such as "10 5".
+fn parse_wagers(input: &str) -> Result<(u32, u32), String> {
+    let amounts: Vec<u32> = input
+        .split_whitespace()
+        .map(|amount| amount.parse())
+        .collect::<Result<_, _>>()
+        .map_err(|_| "Please enter whole dollar amounts".to_string())?;
+    match amounts[..] {
+        [ante] if ante > 0 => Ok((ante, 0)),
+        [ante, pair_plus] if ante > 0 => Ok((ante, pair_plus)),
+        [0] | [0, _] => Err("The ante must be at least $1".to_string()),
+        _ => Err("Please enter an ante and at most one Pair Plus bet".to_string()),
+    }
+}
+
+/// Asks whether to play or fold until a valid answer is given, with a '?' hint from the
+/// Queen-6-4 strategy.
+fn ask_play(ante: u32, play_is_right: bool) -> bool {
+    loop {
+        println!("Play for ${} (p) or fold (f)? ('?' for a hint)", ante);
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        match input.trim().to_lowercase().as_str() {
+            "p" | "play" => return true,
+            "f" | "fold" => return false,
+            "?" if play_is_right => println!("{}", "Play: you have Queen-6-4 or better.".yellow()),
+            "?" => println!("{}", "Fold: you have less than Queen-6-4.".yellow()),
+            _ => println!("Please enter 'p' or 'f'."),
+        }
+    }
+}
+
+/// "Straight flush 40:1, Three of a kind 30:1, ..."
+fn format_paytable(paytable: &[(impl std::fmt::Display, u32)]) -> String {
+    paytable
+        .iter()
+        .map(|(category, pays)| format!("{} {}:1", category, pays))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_wagers() {
+        assert_eq!(parse_wagers("10\n"), Ok((10, 0)));
+        assert_eq!(parse_wagers("10 5"), Ok((10, 5)));
+        assert!(parse_wagers("").is_err());
+        assert!(parse_wagers("0 5").is_err());
+        assert!(parse_wagers("10 5 5").is_err());
+        assert!(parse_wagers("ten").is_err());
+    }
+}
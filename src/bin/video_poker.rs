@@ -0,0 +1,173 @@
+use colored::*;
+use games::cards::{format_card, format_hand, Card, Shoe};
+use games::cli::{format_net, take_flag_value};
+use games::video_poker::Paytable;
+use std::env;
+use std::io;
+use std::process;
+
+fn main() {
+    let mut args: Vec<String> = env::args().skip(1).collect();
+    let paytable = match take_flag_value(&mut args, "--paytable").and_then(|name| {
+        if let Some(arg) = args.first() {
+            return Err(format!("unexpected argument '{}'", arg));
+        }
+        name.map_or(Ok(Paytable::JacksOrBetter), |name| name.parse())
+    }) {
+        Ok(paytable) => paytable,
+        Err(e) => {
+            eprintln!("ERROR: {}", e);
+            eprintln!("Usage: video_poker [--paytable <jacks-or-better|deuces-wild|double-bonus>]");
+            process::exit(1);
+        }
+    };
+
+    println!("Welcome to {} video poker!", paytable);
+    print_paytable(paytable);
+    println!("Hold cards by their numbers, e.g. '1 3 4' or '134', 'all' to stand pat, or '?' for a hint.");
+    let mut shoe = Shoe::new(1, 1.0);
+    let mut balance: u32 = 100;
+    loop {
+        println!("--------------------------------------------------------------");
+        println!(
+            "{} {}",
+            "Your balance: $".green(),
+            balance.to_string().green()
+        );
+        if balance == 0 {
+            println!("{}", "You're out of money. Game over!".red());
+            break;
+        }
+        let Some(bet) = ask_bet(balance) else {
+            break;
+        };
+
+        shoe.reshuffle();
+        let mut hand: [Card; 5] = std::array::from_fn(|_| shoe.draw());
+        let hold = ask_holds(paytable, &hand);
+        for (index, card) in hand.iter_mut().enumerate() {
+            if hold & (1 << index) == 0 {
+                *card = shoe.draw();
+            }
+        }
+        println!("Final hand: {}", format_hand(&hand));
+
+        let net = match paytable.evaluate(&hand) {
+            Some((name, pays)) => {
+                println!(
+                    "{}",
+                    format!("{}! Pays {} for 1.", name, pays).green().bold()
+                );
+                (bet * pays) as i32 - bet as i32
+            }
+            None => {
+                println!("{}", "No win.".red());
+                -(bet as i32)
+            }
+        };
+        balance = balance.saturating_add_signed(net);
+        println!("Net result this hand: {}", format_net(net));
+    }
+}
+
+fn print_paytable(paytable: Paytable) {
+    println!("Paytable (per coin bet):");
+    for (name, pays) in paytable.lines() {
+        println!("  {:<24}{:>4}", name, pays);
+    }
+}
+
+/// Asks for the bet on the next hand until a valid one is given. Returns `None` when the
+/// user quits.
+fn ask_bet(balance: u32) -> Option<u32> {
+    loop {
+        println!("Place your bet (or 'q' to quit)");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim() == "q" {
+            return None;
+        }
+        match input.trim().parse::<u32>() {
+            Ok(bet) if bet > balance => println!("Insufficient balance. Please bet less."),
+            Ok(bet) if bet > 0 => return Some(bet),
+            _ => println!("Please enter a valid bet."),
+        }
+    }
+}
+
+/// Shows the dealt hand and asks which cards to hold until a valid answer is given,
+/// returning them as a mask where bit `i` holds card `i`.
+fn ask_holds(paytable: Paytable, hand: &[Card; 5]) -> u8 {
+    loop {
+        println!("Your hand: {}", format_numbered(hand));
+        println!("Which cards do you hold?");
+        let mut input = String::new();
+        io::stdin()
+            .read_line(&mut input)
+            .expect("Failed to read line");
+        if input.trim() == "?" {
+            let (hold, ev) = paytable.best_hold(hand);
+            let held: Vec<Card> = (0..5)
+                .filter(|index| hold & (1 << index) != 0)
+                .map(|index| hand[index])
+                .collect();
+            let advice = match held.len() {
+                0 => "discard everything".to_string(),
+                _ => format!("hold {}", format_hand(&held)),
+            };
+            println!(
+                "Best play: {} (returns {:.3} per coin on average)",
+                advice.yellow(),
+                ev
+            );
+            continue;
+        }
+        match parse_holds(&input) {
+            Ok(hold) => return hold,
+            Err(e) => println!("{}", e),
+        }
+    }
+}
+
+/// Reads which cards to hold, given by their positions 1 to 5 with or without spaces, or
+/// "all". Nothing at all discards the whole hand.
+fn parse_holds(input: &str) -> Result<u8, String> {
+    let input = input.trim();
+    if input.eq_ignore_ascii_case("all") {
+        return Ok(0b11111);
+    }
+    let mut hold = 0;
+    for c in input.chars().filter(|c| !c.is_whitespace() && *c != ',') {
+        match c.to_digit(10) {
+            Some(position @ 1..=5) => hold |= 1 << (position - 1),
+            _ => return Err(format!("'{}' is not a card position from 1 to 5", c)),
+        }
+    }
+    Ok(hold)
+}
+
+/// The cards with their positions, e.g. "1) Ace of Spades, 2) ...".
+fn format_numbered(hand: &[Card]) -> String {
+    hand.iter()
+        .enumerate()
+        .map(|(index, card)| format!("{}) {}", index + 1, format_card(card)))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_holds() {
+        assert_eq!(parse_holds("1 3 4"), Ok(0b01101));
+        assert_eq!(parse_holds("134\n"), Ok(0b01101));
+        assert_eq!(parse_holds("all"), Ok(0b11111));
+        assert_eq!(parse_holds(""), Ok(0));
+        assert!(parse_holds("6").is_err());
+        assert!(parse_holds("1 x").is_err());
+    }
+}
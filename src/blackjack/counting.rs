@@ -0,0 +1,123 @@
+use crate::cards::{Card, Rank};
+
+/// A card counting system: the tag added to the running count for each rank, from Two
+/// up to Ace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CountingSystem {
+    pub name: &'static str,
+    tags: [i8; 13],
+    /// Balanced systems sum to zero over a deck and are converted to a true count.
+    /// Unbalanced ones are played off the running count alone.
+    pub balanced: bool,
+}
+
+//                                                 2  3  4  5  6  7  8  9  T  J  Q  K  A
+pub const HI_LO: CountingSystem = CountingSystem {
+    name: "Hi-Lo",
+    tags: [1, 1, 1, 1, 1, 0, 0, 0, -1, -1, -1, -1, -1],
+    balanced: true,
+};
+pub const KO: CountingSystem = CountingSystem {
+    name: "KO",
+    tags: [1, 1, 1, 1, 1, 1, 0, 0, -1, -1, -1, -1, -1],
+    balanced: false,
+};
+pub const OMEGA_II: CountingSystem = CountingSystem {
+    name: "Omega II",
+    tags: [1, 1, 2, 2, 2, 1, 0, -1, -2, -2, -2, -2, 0],
+    balanced: true,
+};
+pub const ZEN: CountingSystem = CountingSystem {
+    name: "Zen",
+    tags: [1, 1, 2, 2, 2, 1, 0, 0, -2, -2, -2, -2, -1],
+    balanced: true,
+};
+
+pub const COUNTING_SYSTEMS: [CountingSystem; 4] = [HI_LO, KO, OMEGA_II, ZEN];
+
+impl CountingSystem {
+    /// Looks a system up by name, ignoring case, spaces and dashes ("hi-lo", "omega2").
+    pub fn by_name(name: &str) -> Option<CountingSystem> {
+        let simplify = |s: &str| {
+            s.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+                .replace("ii", "2")
+        };
+        COUNTING_SYSTEMS
+            .iter()
+            .find(|system| simplify(system.name) == simplify(name))
+            .copied()
+    }
+
+    pub fn tag(&self, rank: Rank) -> i32 {
+        let index = Rank::ALL.iter().position(|&r| r == rank).unwrap();
+        self.tags[index] as i32
+    }
+
+    /// Where the running count starts for a fresh shoe. Unbalanced KO starts at
+    /// 4 - 4 x decks so that its key count is the same for any number of decks.
+    pub fn initial_count(&self, decks: usize) -> i32 {
+        if self.balanced {
+            0
+        } else {
+            4 - 4 * decks as i32
+        }
+    }
+
+    /// The running count after `dealt` cards have come out of a fresh shoe of `decks` decks.
+    pub fn running_count(&self, decks: usize, dealt: &[Card]) -> i32 {
+        self.initial_count(decks) + dealt.iter().map(|card| self.tag(card.rank)).sum::<i32>()
+    }
+
+    /// The running count per deck still to be dealt. Unbalanced systems are not converted,
+    /// so their running count is returned as is.
+    pub fn true_count(&self, running_count: i32, cards_remaining: usize) -> f64 {
+        if !self.balanced {
+            return running_count as f64;
+        }
+        // Never divide by less than half a deck, as dealers won't deal that deep anyway
+        let decks_remaining = (cards_remaining as f64 / 52.0).max(0.5);
+        running_count as f64 / decks_remaining
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{create_deck, Suit};
+
+    #[test]
+    fn test_balanced_systems_sum_to_zero_over_a_deck() {
+        let deck = create_deck();
+        for system in COUNTING_SYSTEMS {
+            let total = system.running_count(1, &deck);
+            if system.balanced {
+                assert_eq!(total, 0, "{} should be balanced", system.name);
+            } else {
+                assert_eq!(total, 4, "{} should end a deck at +4", system.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_running_and_true_count() {
+        let low_cards: Vec<Card> = [Rank::Two, Rank::Five, Rank::Six, Rank::Nine]
+            .iter()
+            .map(|&r| Card::new(r, Suit::Clubs))
+            .collect();
+        assert_eq!(HI_LO.running_count(6, &low_cards), 3);
+        assert_eq!(HI_LO.true_count(3, 156), 1.0);
+        assert_eq!(KO.running_count(6, &low_cards), -17);
+        assert_eq!(KO.true_count(-17, 156), -17.0);
+    }
+
+    #[test]
+    fn test_lookup_by_name() {
+        assert_eq!(CountingSystem::by_name("hi-lo"), Some(HI_LO));
+        assert_eq!(CountingSystem::by_name("Omega 2"), Some(OMEGA_II));
+        assert_eq!(CountingSystem::by_name("omega-ii"), Some(OMEGA_II));
+        assert_eq!(CountingSystem::by_name("red 7"), None);
+    }
+}
@@ -0,0 +1,154 @@
+use crate::cards::{Card, Rank};
+
+/// The blackjack value of a rank, counting an Ace as 1.
+pub fn card_value(rank: Rank) -> u8 {
+    match rank {
+        Rank::Two => 2,
+        Rank::Three => 3,
+        Rank::Four => 4,
+        Rank::Five => 5,
+        Rank::Six => 6,
+        Rank::Seven => 7,
+        Rank::Eight => 8,
+        Rank::Nine => 9,
+        Rank::Ten | Rank::Jack | Rank::Queen | Rank::King => 10,
+        Rank::Ace => 1,
+    }
+}
+
+/// A blackjack hand. The hard total (every Ace counted as 1) and the number of Aces are
+/// kept up to date as cards are added, so the best total never has to be recomputed
+/// from the cards.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Hand {
+    cards: Vec<Card>,
+    hard: u8,
+    aces: u8,
+}
+
+impl Hand {
+    pub fn new() -> Self {
+        Hand::default()
+    }
+
+    pub fn from_cards(cards: &[Card]) -> Self {
+        let mut hand = Hand::new();
+        for &card in cards {
+            hand.push(card);
+        }
+        hand
+    }
+
+    pub fn push(&mut self, card: Card) {
+        self.hard += card_value(card.rank);
+        if card.rank == Rank::Ace {
+            self.aces += 1;
+        }
+        self.cards.push(card);
+    }
+
+    /// Removes the last card, e.g. to move it into a new hand when splitting.
+    pub fn pop(&mut self) -> Option<Card> {
+        let card = self.cards.pop()?;
+        self.hard -= card_value(card.rank);
+        if card.rank == Rank::Ace {
+            self.aces -= 1;
+        }
+        Some(card)
+    }
+
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// The total with every Ace counted as 1.
+    pub fn hard_total(&self) -> u8 {
+        self.hard
+    }
+
+    /// True when one Ace is being counted as 11 without busting the hand.
+    pub fn is_soft(&self) -> bool {
+        self.aces > 0 && self.hard + 10 <= 21
+    }
+
+    /// The best total for the hand: one Ace counts as 11 whenever that does not bust it.
+    pub fn value(&self) -> u8 {
+        if self.is_soft() {
+            self.hard + 10
+        } else {
+            self.hard
+        }
+    }
+
+    pub fn is_bust(&self) -> bool {
+        self.hard > 21
+    }
+
+    /// A two card 21.
+    pub fn is_blackjack(&self) -> bool {
+        self.cards.len() == 2 && self.value() == 21
+    }
+
+    /// A hand can be split when it holds exactly two cards of the same rank.
+    pub fn can_split(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].rank == self.cards[1].rank
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Suit;
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let cards: Vec<Card> = ranks.iter().map(|&r| Card::new(r, Suit::Spades)).collect();
+        Hand::from_cards(&cards)
+    }
+
+    #[test]
+    fn test_soft_and_hard_totals() {
+        let soft = hand(&[Rank::Ace, Rank::Six]);
+        assert_eq!(soft.value(), 17);
+        assert!(soft.is_soft());
+        assert_eq!(soft.hard_total(), 7);
+
+        let hard = hand(&[Rank::Ace, Rank::Six, Rank::King]);
+        assert_eq!(hard.value(), 17);
+        assert!(!hard.is_soft());
+    }
+
+    #[test]
+    fn test_multiple_aces() {
+        assert_eq!(hand(&[Rank::Ace, Rank::Ace]).value(), 12);
+        assert_eq!(hand(&[Rank::Ace, Rank::Ace, Rank::Nine]).value(), 21);
+        assert_eq!(
+            hand(&[Rank::Ace, Rank::Ace, Rank::King, Rank::King]).value(),
+            22
+        );
+    }
+
+    #[test]
+    fn test_blackjack_bust_and_split() {
+        assert!(hand(&[Rank::Ace, Rank::Queen]).is_blackjack());
+        assert!(!hand(&[Rank::Seven, Rank::Seven, Rank::Seven]).is_blackjack());
+        assert!(hand(&[Rank::King, Rank::Queen, Rank::Two]).is_bust());
+        assert!(hand(&[Rank::Eight, Rank::Eight]).can_split());
+        assert!(!hand(&[Rank::King, Rank::Queen]).can_split());
+    }
+
+    #[test]
+    fn test_pop_restores_totals() {
+        let mut pair = hand(&[Rank::Ace, Rank::Ace]);
+        assert_eq!(pair.pop().map(|c| c.rank), Some(Rank::Ace));
+        assert_eq!(pair.value(), 11);
+        assert!(pair.is_soft());
+    }
+}
@@ -0,0 +1,48 @@
+use crate::cards::{Card, Rank};
+
+/// Insurance is a side bet, offered when the dealer shows an Ace, that the hole card
+/// completes a dealer blackjack.
+pub fn insurance_offered(up_card: &Card) -> bool {
+    up_card.rank == Rank::Ace
+}
+
+/// The largest insurance bet allowed against a main bet: half of it.
+pub fn max_insurance(bet: u32) -> u32 {
+    bet / 2
+}
+
+/// How an insurance bet was settled once the dealer's hole card is known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InsuranceResult {
+    /// The dealer had blackjack. Holds the winnings at 2:1, not including the stake.
+    Won(u32),
+    /// The dealer did not have blackjack. Holds the lost stake.
+    Lost(u32),
+}
+
+pub fn settle_insurance(stake: u32, dealer_blackjack: bool) -> InsuranceResult {
+    if dealer_blackjack {
+        InsuranceResult::Won(stake * 2)
+    } else {
+        InsuranceResult::Lost(stake)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Suit;
+
+    #[test]
+    fn test_insurance_only_against_an_ace() {
+        assert!(insurance_offered(&Card::new(Rank::Ace, Suit::Hearts)));
+        assert!(!insurance_offered(&Card::new(Rank::King, Suit::Hearts)));
+        assert_eq!(max_insurance(25), 12);
+    }
+
+    #[test]
+    fn test_insurance_pays_two_to_one() {
+        assert_eq!(settle_insurance(5, true), InsuranceResult::Won(10));
+        assert_eq!(settle_insurance(5, false), InsuranceResult::Lost(5));
+    }
+}
@@ -0,0 +1,26 @@
+//! Blackjack rules and hand evaluation, kept free of any terminal I/O.
+
+mod counting;
+mod hand;
+mod insurance;
+mod probability;
+mod rules;
+mod settlement;
+mod side_bets;
+mod simulation;
+mod strategy;
+mod variant;
+
+pub use counting::{CountingSystem, COUNTING_SYSTEMS, HI_LO, KO, OMEGA_II, ZEN};
+pub use hand::{card_value, Hand};
+pub use insurance::{insurance_offered, max_insurance, settle_insurance, InsuranceResult};
+pub use probability::{action_evs, dealer_odds, DealerOdds};
+pub use rules::{surrender_loss, DoubleOn, HoleCard, Payout, Surrender, TableRules};
+pub use settlement::{settle, spot_hands, HandResult, Outcome, PlayerHand};
+pub use side_bets::SideBet;
+pub use simulation::{
+    play_hands, play_round, simulate, BasicStrategy, Counter, HandEvent, MimicDealer, NeverBust,
+    Reckless, RoundResult, Stats, Strategy, StrategyKind, BASE_BET,
+};
+pub use strategy::{basic_strategy, Action};
+pub use variant::{should_switch, switch_cards, switch_hands, Variant};
@@ -0,0 +1,340 @@
+use super::rules::{Surrender, TableRules};
+use super::strategy::Action;
+use super::{card_value, Hand};
+use crate::cards::{Card, Rank, Suit};
+use std::collections::HashMap;
+
+/// One rank standing in for each blackjack value, Ace (1) first and Ten (10) last.
+const VALUE_RANKS: [Rank; 10] = [
+    Rank::Ace,
+    Rank::Two,
+    Rank::Three,
+    Rank::Four,
+    Rank::Five,
+    Rank::Six,
+    Rank::Seven,
+    Rank::Eight,
+    Rank::Nine,
+    Rank::Ten,
+];
+
+/// The chance of each way the dealer's hand can finish. The seven chances add up to one.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct DealerOdds {
+    /// Chances of standing on 17, 18, 19, 20 and 21.
+    pub totals: [f64; 5],
+    pub bust: f64,
+    pub blackjack: f64,
+    /// The part of `bust` where the dealer finishes on exactly 22.
+    pub twenty_two: f64,
+}
+
+/// How many cards of each value are left, indexed by value - 1.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+struct Composition([u32; 10]);
+
+impl Composition {
+    fn from_cards(cards: &[Card]) -> Self {
+        let mut counts = [0; 10];
+        for card in cards {
+            counts[card_value(card.rank) as usize - 1] += 1;
+        }
+        Composition(counts)
+    }
+
+    /// Every value still in the shoe, with its card and the chance of drawing it next.
+    fn draws(&self) -> Vec<(usize, Card, f64)> {
+        let total: u32 = self.0.iter().sum();
+        (0..10)
+            .filter(|&i| self.0[i] > 0)
+            .map(|i| {
+                let card = Card::new(VALUE_RANKS[i], Suit::Spades);
+                (i, card, self.0[i] as f64 / total as f64)
+            })
+            .collect()
+    }
+}
+
+/// Works out how the dealer's hand finishes from `up_card`, drawing from exactly the
+/// `unseen` cards (everything the player cannot see, the hole card included). When the
+/// rules have the dealer peek at this up card, blackjack has already been ruled out.
+pub fn dealer_odds(up_card: Rank, unseen: &[Card], rules: &TableRules) -> DealerOdds {
+    let mut odds = DealerOdds::default();
+    let mut hand = Hand::from_cards(&[Card::new(up_card, Suit::Spades)]);
+    let mut shoe = Composition::from_cards(unseen);
+    let peeked = rules.dealer_peeks(&hand.cards()[0]);
+    play_dealer(&mut hand, &mut shoe, rules, peeked, 1.0, &mut odds);
+    odds
+}
+
+/// Adds `chance` times every way the dealer can go on from `hand` to `odds`, following
+/// the dealer's drawing rules card by card.
+fn play_dealer(
+    hand: &mut Hand,
+    shoe: &mut Composition,
+    rules: &TableRules,
+    peeked: bool,
+    chance: f64,
+    odds: &mut DealerOdds,
+) {
+    if hand.len() >= 2 {
+        if hand.is_blackjack() {
+            odds.blackjack += chance;
+            return;
+        }
+        if hand.is_bust() {
+            odds.bust += chance;
+            if hand.value() == 22 {
+                odds.twenty_two += chance;
+            }
+            return;
+        }
+        if !rules.dealer_should_hit(hand) {
+            odds.totals[hand.value() as usize - 17] += chance;
+            return;
+        }
+    }
+    let mut draws = shoe.draws();
+    if peeked && hand.len() == 1 {
+        // The hole card is known not to make blackjack
+        draws.retain(|&(_, card, _)| !Hand::from_cards(&[hand.cards()[0], card]).is_blackjack());
+        let total: f64 = draws.iter().map(|&(_, _, p)| p).sum();
+        for draw in draws.iter_mut() {
+            draw.2 /= total;
+        }
+    }
+    for (i, card, p) in draws {
+        shoe.0[i] -= 1;
+        hand.push(card);
+        play_dealer(hand, shoe, rules, peeked, chance * p, odds);
+        hand.pop();
+        shoe.0[i] += 1;
+    }
+}
+
+/// The expected value of every action the rules allow on `hand`, in units of the hand's
+/// bet, drawing from the `unseen` cards (the undealt shoe plus the dealer's hole card).
+///
+/// Every decision after the first is played to maximise EV. Splits are worked out as two
+/// copies of one hand without resplitting, ignoring the cards the other hand takes. A
+/// variant's rule that 21 always wins is counted, but not its bonus payouts or rescues.
+pub fn action_evs(
+    hand: &Hand,
+    up_card: Rank,
+    hand_count: usize,
+    unseen: &[Card],
+    rules: &TableRules,
+) -> Vec<(Action, f64)> {
+    let odds = dealer_odds(up_card, unseen, rules);
+    let mut calculator = Calculator {
+        rules,
+        up_card,
+        shoe: Composition::from_cards(unseen),
+        dealer: HashMap::new(),
+        hits: HashMap::new(),
+    };
+    let original_bets_only = rules.original_bets_only;
+    // Dealer blackjacks the player could not rule out, with what each action loses to one
+    let blackjack = odds.blackjack;
+    let mut evs = vec![
+        (Action::Stand, -1.0, calculator.stand(hand.value())),
+        (Action::Hit, -1.0, calculator.hit(&mut hand.clone())),
+    ];
+    if rules.can_double(hand, hand_count) {
+        let lost = if original_bets_only { -1.0 } else { -2.0 };
+        evs.push((Action::Double, lost, calculator.double(&mut hand.clone())));
+    }
+    if rules.can_split(hand, hand_count) {
+        let lost = if original_bets_only { -1.0 } else { -2.0 };
+        evs.push((Action::Split, lost, calculator.split(hand)));
+    }
+    if rules.can_surrender(hand, hand_count) {
+        let lost = if rules.surrender == Surrender::Early {
+            -0.5
+        } else {
+            -1.0
+        };
+        evs.push((Action::Surrender, lost, -0.5));
+    }
+    evs.into_iter()
+        .map(|(action, lost, ev)| (action, blackjack * lost + (1.0 - blackjack) * ev))
+        .collect()
+}
+
+/// Works out player EVs against a dealer without blackjack, remembering results that
+/// come up again by a different order of cards.
+struct Calculator<'a> {
+    rules: &'a TableRules,
+    up_card: Rank,
+    shoe: Composition,
+    dealer: HashMap<Composition, DealerOdds>,
+    hits: HashMap<(Composition, u8, bool), f64>,
+}
+
+impl Calculator<'_> {
+    /// The dealer's odds from the cards left right now, given no dealer blackjack.
+    fn dealer(&mut self) -> DealerOdds {
+        if let Some(&odds) = self.dealer.get(&self.shoe) {
+            return odds;
+        }
+        let mut odds = DealerOdds::default();
+        let mut hand = Hand::from_cards(&[Card::new(self.up_card, Suit::Spades)]);
+        play_dealer(&mut hand, &mut self.shoe, self.rules, true, 1.0, &mut odds);
+        self.dealer.insert(self.shoe, odds);
+        odds
+    }
+
+    fn stand(&mut self, total: u8) -> f64 {
+        if total > 21 {
+            return -1.0;
+        }
+        if total == 21 && self.rules.variant.player_21_always_wins() {
+            return 1.0;
+        }
+        let odds = self.dealer();
+        let mut ev = odds.bust;
+        if self.rules.variant.dealer_22_pushes() {
+            ev -= odds.twenty_two;
+        }
+        for (dealer_total, chance) in (17..=21).zip(odds.totals) {
+            if total > dealer_total {
+                ev += chance;
+            } else if total < dealer_total {
+                ev -= chance;
+            }
+        }
+        ev
+    }
+
+    /// Takes one card, then carries on hitting or stands, whichever is worth more.
+    fn hit(&mut self, hand: &mut Hand) -> f64 {
+        let key = (self.shoe, hand.value(), hand.is_soft());
+        if let Some(&ev) = self.hits.get(&key) {
+            return ev;
+        }
+        let mut ev = 0.0;
+        for (i, card, p) in self.shoe.draws() {
+            self.shoe.0[i] -= 1;
+            hand.push(card);
+            ev += p * if hand.is_bust() {
+                -1.0
+            } else {
+                self.stand(hand.value()).max(self.hit(hand))
+            };
+            hand.pop();
+            self.shoe.0[i] += 1;
+        }
+        self.hits.insert(key, ev);
+        ev
+    }
+
+    fn double(&mut self, hand: &mut Hand) -> f64 {
+        let mut ev = 0.0;
+        for (i, card, p) in self.shoe.draws() {
+            self.shoe.0[i] -= 1;
+            hand.push(card);
+            ev += p * 2.0 * self.stand(hand.value());
+            hand.pop();
+            self.shoe.0[i] += 1;
+        }
+        ev
+    }
+
+    /// Twice the EV of one of the two hands, each played as well as the rules allow.
+    fn split(&mut self, hand: &Hand) -> f64 {
+        let first = hand.cards()[0];
+        let one_card_only = first.rank == Rank::Ace && !self.rules.hit_split_aces;
+        let mut ev = 0.0;
+        for (i, card, p) in self.shoe.draws() {
+            self.shoe.0[i] -= 1;
+            let mut split_hand = Hand::from_cards(&[first, card]);
+            let mut best = self.stand(split_hand.value());
+            if !one_card_only {
+                best = best.max(self.hit(&mut split_hand));
+                if self.rules.can_double(&split_hand, 2) {
+                    best = best.max(self.double(&mut split_hand));
+                }
+            }
+            ev += p * best;
+            self.shoe.0[i] += 1;
+        }
+        2.0 * ev
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::blackjack::HoleCard;
+    use crate::cards::create_deck;
+
+    fn cards(ranks: &[Rank]) -> Vec<Card> {
+        ranks.iter().map(|&r| Card::new(r, Suit::Clubs)).collect()
+    }
+
+    fn ev_of(evs: &[(Action, f64)], action: Action) -> f64 {
+        evs.iter().find(|&&(a, _)| a == action).unwrap().1
+    }
+
+    #[test]
+    fn test_dealer_odds_add_up() {
+        let rules = TableRules::default();
+        let mut deck = create_deck();
+        deck.retain(|card| card.rank != Rank::Seven || card.suit != Suit::Hearts);
+        let odds = dealer_odds(Rank::Seven, &deck, &rules);
+        let total = odds.totals.iter().sum::<f64>() + odds.bust + odds.blackjack;
+        assert!((total - 1.0).abs() < 1e-9);
+        assert_eq!(odds.blackjack, 0.0);
+        // A seven most often makes 17
+        assert!(odds.totals[0] > 0.3);
+    }
+
+    #[test]
+    fn test_dealer_odds_with_a_stacked_shoe() {
+        let rules = TableRules::default();
+        // 6 + 10 = 16 must hit and the next ten busts it
+        let tens = cards(&[Rank::Ten, Rank::King, Rank::Queen]);
+        assert_eq!(dealer_odds(Rank::Six, &tens, &rules).bust, 1.0);
+
+        // After a peek the Ace can't be under a ten, so the dealer must have the 8 for 18
+        let unseen = cards(&[Rank::Ace, Rank::Eight]);
+        let odds = dealer_odds(Rank::Ten, &unseen, &rules);
+        assert_eq!(odds.totals[1], 1.0);
+        let european = TableRules {
+            hole_card: HoleCard::European,
+            ..TableRules::default()
+        };
+        let odds = dealer_odds(Rank::Ten, &unseen, &european);
+        assert_eq!((odds.blackjack, odds.totals[1]), (0.5, 0.5));
+    }
+
+    #[test]
+    fn test_action_evs_with_a_known_shoe() {
+        let rules = TableRules::default();
+        let hand = Hand::from_cards(&cards(&[Rank::Ten, Rank::Six]));
+        // Only fives left: the dealer's 6 + 5 + 5 + 5 = 21, and a hit pushes with 21
+        let fives = cards(&[Rank::Five; 8]);
+        let evs = action_evs(&hand, Rank::Six, 1, &fives, &rules);
+        assert_eq!(ev_of(&evs, Action::Stand), -1.0);
+        assert_eq!(ev_of(&evs, Action::Hit), 0.0);
+        assert_eq!(evs.len(), 3);
+        assert_eq!(ev_of(&evs, Action::Double), 0.0);
+    }
+
+    #[test]
+    fn test_sixteen_against_ten() {
+        // Close to the textbook numbers for 16 against a ten from a full six deck shoe
+        let rules = TableRules::default();
+        let hand = Hand::from_cards(&cards(&[Rank::Ten, Rank::Six]));
+        let mut unseen: Vec<Card> = (0..6).flat_map(|_| create_deck()).collect();
+        for card in cards(&[Rank::Ten, Rank::Six, Rank::Ten]) {
+            let index = unseen.iter().position(|&c| c == card).unwrap();
+            unseen.remove(index);
+        }
+        let evs = action_evs(&hand, Rank::Ten, 1, &unseen, &rules);
+        let stand = ev_of(&evs, Action::Stand);
+        let hit = ev_of(&evs, Action::Hit);
+        assert!((stand + 0.54).abs() < 0.01, "stand {}", stand);
+        assert!((hit + 0.54).abs() < 0.01, "hit {}", hit);
+    }
+}
@@ -0,0 +1,571 @@
+use super::variant::Variant;
+use super::Hand;
+use crate::cards::{Card, Rank, Shoe, DEFAULT_PENETRATION, MAX_DECKS};
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+/// How much a winning hand pays, as "numerator:denominator" of the stake (e.g. 3:2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Payout {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Payout {
+    pub const THREE_TO_TWO: Payout = Payout {
+        numerator: 3,
+        denominator: 2,
+    };
+    pub const SIX_TO_FIVE: Payout = Payout {
+        numerator: 6,
+        denominator: 5,
+    };
+    pub const ONE_TO_ONE: Payout = Payout {
+        numerator: 1,
+        denominator: 1,
+    };
+
+    /// The winnings (not including the returned stake) for a bet of `bet`, rounded down.
+    pub fn apply(self, bet: u32) -> u32 {
+        bet * self.numerator / self.denominator
+    }
+}
+
+impl fmt::Display for Payout {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{}", self.numerator, self.denominator)
+    }
+}
+
+impl FromStr for Payout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = s
+            .split_once(':')
+            .ok_or_else(|| format!("expected a payout like 3:2, got '{}'", s))?;
+        match (numerator.trim().parse(), denominator.trim().parse()) {
+            (Ok(numerator), Ok(denominator)) if numerator > 0 && denominator > 0 => Ok(Payout {
+                numerator,
+                denominator,
+            }),
+            _ => Err(format!("expected a payout like 3:2, got '{}'", s)),
+        }
+    }
+}
+
+/// When the dealer takes the hole card, and so whether they can check it for blackjack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HoleCard {
+    /// The hole card is dealt up front and the dealer peeks under an Ace or ten-value up
+    /// card, ending the round at once on a blackjack.
+    American,
+    /// European no-hole-card (ENHC): the dealer's second card is only drawn after every
+    /// player has acted.
+    European,
+}
+
+impl fmt::Display for HoleCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            HoleCard::American => "american",
+            HoleCard::European => "european",
+        })
+    }
+}
+
+impl FromStr for HoleCard {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "american" | "peek" => Ok(HoleCard::American),
+            "european" | "enhc" => Ok(HoleCard::European),
+            _ => Err(format!(
+                "hole_card must be american or european, got '{}'",
+                s
+            )),
+        }
+    }
+}
+
+/// Whether the player may give up a hand for half of the bet, and when.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surrender {
+    None,
+    /// Only after the dealer has checked for blackjack. Without a hole card, a surrender
+    /// is void if the dealer goes on to make blackjack.
+    Late,
+    /// Before the dealer checks for blackjack, so the half bet is saved even against one.
+    Early,
+}
+
+impl fmt::Display for Surrender {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Surrender::None => "none",
+            Surrender::Late => "late",
+            Surrender::Early => "early",
+        })
+    }
+}
+
+impl FromStr for Surrender {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "none" | "no" => Ok(Surrender::None),
+            "late" => Ok(Surrender::Late),
+            "early" => Ok(Surrender::Early),
+            _ => Err(format!(
+                "surrender must be none, late or early, got '{}'",
+                s
+            )),
+        }
+    }
+}
+
+/// The part of `bet` forfeited by surrendering. Half is returned, rounded down.
+pub fn surrender_loss(bet: u32) -> u32 {
+    bet - bet / 2
+}
+
+/// Which two card totals the player may double down on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DoubleOn {
+    AnyTwo,
+    NineToEleven,
+    TenToEleven,
+}
+
+impl DoubleOn {
+    /// Restricted doubles only apply to hard totals, so a soft hand can only be doubled
+    /// under `AnyTwo`.
+    pub fn allows(self, hand: &Hand) -> bool {
+        match self {
+            DoubleOn::AnyTwo => true,
+            DoubleOn::NineToEleven => !hand.is_soft() && (9..=11).contains(&hand.value()),
+            DoubleOn::TenToEleven => !hand.is_soft() && (10..=11).contains(&hand.value()),
+        }
+    }
+}
+
+impl fmt::Display for DoubleOn {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            DoubleOn::AnyTwo => "any",
+            DoubleOn::NineToEleven => "9-11",
+            DoubleOn::TenToEleven => "10-11",
+        })
+    }
+}
+
+impl FromStr for DoubleOn {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "any" => Ok(DoubleOn::AnyTwo),
+            "9-11" => Ok(DoubleOn::NineToEleven),
+            "10-11" => Ok(DoubleOn::TenToEleven),
+            _ => Err(format!("double_on must be any, 9-11 or 10-11, got '{}'", s)),
+        }
+    }
+}
+
+/// The house rules a blackjack table is played under.
+///
+/// Rules can be read from a config file of `key = value` lines (`#` starts a comment) or
+/// set one at a time with `set`, using the same keys:
+///
+/// ```text
+/// variant = classic           # classic, spanish21 or switch
+/// decks = 6
+/// penetration = 75            # percent of the shoe dealt before reshuffling
+/// dealer_hits_soft_17 = true
+/// blackjack_pays = 6:5
+/// double_after_split = true
+/// double_on = any             # any, 9-11 or 10-11
+/// max_split_hands = 4
+/// resplit_aces = false
+/// hit_split_aces = false
+/// hole_card = american        # american (peek) or european (no hole card)
+/// original_bets_only = false  # european only: a dealer blackjack takes just the original bet
+/// surrender = none            # none, late or early (early needs a one hand variant)
+/// ```
+///
+/// Choosing a variant also sets the rules it is usually dealt with, so put it first and
+/// any later line can still change them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableRules {
+    pub variant: Variant,
+    pub decks: usize,
+    /// Fraction of the shoe dealt before the cut card comes out.
+    pub penetration: f64,
+    pub dealer_hits_soft_17: bool,
+    pub blackjack_pays: Payout,
+    pub double_after_split: bool,
+    pub double_on: DoubleOn,
+    /// The most hands a player can end up with by splitting and resplitting.
+    pub max_split_hands: usize,
+    pub resplit_aces: bool,
+    pub hit_split_aces: bool,
+    pub hole_card: HoleCard,
+    /// Under `HoleCard::European`, a dealer blackjack only takes the original bet and any
+    /// doubled or split stakes are returned.
+    pub original_bets_only: bool,
+    pub surrender: Surrender,
+}
+
+impl Default for TableRules {
+    fn default() -> Self {
+        TableRules {
+            variant: Variant::Classic,
+            decks: 6,
+            penetration: DEFAULT_PENETRATION,
+            dealer_hits_soft_17: false,
+            blackjack_pays: Payout::THREE_TO_TWO,
+            double_after_split: true,
+            double_on: DoubleOn::AnyTwo,
+            max_split_hands: 4,
+            resplit_aces: false,
+            hit_split_aces: false,
+            hole_card: HoleCard::American,
+            original_bets_only: false,
+            surrender: Surrender::None,
+        }
+    }
+}
+
+impl TableRules {
+    /// Reads a rules file, starting from the default rules for anything it leaves out.
+    pub fn load(path: &str) -> Result<Self, String> {
+        let mut rules = TableRules::default();
+        rules.apply_file(path)?;
+        Ok(rules)
+    }
+
+    /// Applies a rules file on top of the current rules.
+    pub fn apply_file(&mut self, path: &str) -> Result<(), String> {
+        let contents =
+            fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+        self.apply_config(&contents)
+            .map_err(|e| format!("{}: {}", path, e))
+    }
+
+    /// Applies every `key = value` line of a config file on top of the current rules.
+    pub fn apply_config(&mut self, contents: &str) -> Result<(), String> {
+        for (line_number, line) in contents.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| format!("line {}: expected 'key = value'", line_number + 1))?;
+            self.set(key.trim(), value.trim())
+                .map_err(|e| format!("line {}: {}", line_number + 1, e))?;
+        }
+        Ok(())
+    }
+
+    /// Builds the rules from command line arguments. `--config <file>` applies a rules file
+    /// and every other `--some-rule <value>` flag sets the rule `some_rule`, in the order given.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let mut rules = TableRules::default();
+        let mut args = args.iter();
+        while let Some(flag) = args.next() {
+            let key = flag
+                .strip_prefix("--")
+                .ok_or_else(|| format!("unexpected argument {}", flag))?;
+            let value = args
+                .next()
+                .ok_or_else(|| format!("missing value for {}", flag))?;
+            if key == "config" {
+                rules.apply_file(value)?;
+            } else {
+                rules.set(&key.replace('-', "_"), value)?;
+            }
+        }
+        Ok(rules)
+    }
+
+    /// Sets a single rule from its config key and textual value.
+    pub fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "variant" => {
+                self.variant = value.parse()?;
+                match self.variant {
+                    Variant::Classic => {}
+                    Variant::Spanish21 => {
+                        self.double_after_split = true;
+                        self.resplit_aces = true;
+                        self.hit_split_aces = true;
+                        self.surrender = Surrender::Late;
+                    }
+                    Variant::Switch => {
+                        self.blackjack_pays = Payout::ONE_TO_ONE;
+                        self.dealer_hits_soft_17 = true;
+                        self.surrender = Surrender::None;
+                    }
+                }
+            }
+            "decks" => {
+                self.decks = match value.parse() {
+                    Ok(n) if (1..=MAX_DECKS).contains(&n) => n,
+                    _ => return Err(format!("decks must be between 1 and {}", MAX_DECKS)),
+                }
+            }
+            "penetration" => {
+                self.penetration = match value.trim_end_matches('%').parse::<f64>() {
+                    Ok(p) if p > 0.0 && p <= 100.0 => p / 100.0,
+                    _ => return Err("penetration must be a percentage between 1 and 100".into()),
+                }
+            }
+            "dealer_hits_soft_17" => self.dealer_hits_soft_17 = parse_bool(key, value)?,
+            "blackjack_pays" => self.blackjack_pays = value.parse()?,
+            "double_after_split" => self.double_after_split = parse_bool(key, value)?,
+            "double_on" => self.double_on = value.parse()?,
+            "max_split_hands" => {
+                self.max_split_hands = match value.parse() {
+                    Ok(n) if n >= 1 => n,
+                    _ => return Err("max_split_hands must be at least 1".into()),
+                }
+            }
+            "resplit_aces" => self.resplit_aces = parse_bool(key, value)?,
+            "hit_split_aces" => self.hit_split_aces = parse_bool(key, value)?,
+            "hole_card" => self.hole_card = value.parse()?,
+            "original_bets_only" => self.original_bets_only = parse_bool(key, value)?,
+            "surrender" => {
+                let surrender = value.parse()?;
+                // Early surrender is offered on one hand before the peek, so it has no
+                // place in a variant that deals several hands
+                if surrender == Surrender::Early && self.variant.spots() > 1 {
+                    return Err(format!(
+                        "early surrender is not offered in the {} variant",
+                        self.variant
+                    ));
+                }
+                self.surrender = surrender;
+            }
+            _ => return Err(format!("unknown rule '{}'", key)),
+        }
+        Ok(())
+    }
+
+    /// The dealer draws below 17, and on a soft 17 when the table is H17.
+    pub fn dealer_should_hit(&self, dealer_hand: &Hand) -> bool {
+        let value = dealer_hand.value();
+        value < 17 || (value == 17 && dealer_hand.is_soft() && self.dealer_hits_soft_17)
+    }
+
+    /// Whether the dealer checks the hole card for blackjack before the player acts. Only
+    /// an Ace or ten-value up card can make a blackjack, and only if there is a hole card.
+    pub fn dealer_peeks(&self, up_card: &Card) -> bool {
+        self.hole_card == HoleCard::American
+            && (up_card.rank == Rank::Ace || up_card.rank.is_ten_value())
+    }
+
+    /// A fresh shoe of `decks` of the variant's deck, cut at `penetration`.
+    pub fn shoe(&self) -> Shoe {
+        Shoe::with_deck(self.variant.deck(), self.decks, self.penetration)
+    }
+
+    /// Doubling is only offered on the first two cards of a hand unless the variant allows
+    /// it on any number, restricted further by `double_on` and, once the player has split,
+    /// by `double_after_split`.
+    pub fn can_double(&self, hand: &Hand, hand_count: usize) -> bool {
+        (hand.len() == 2 || (hand.len() > 2 && self.variant.double_any_cards()))
+            && (hand_count == 1 || self.double_after_split)
+            && self.double_on.allows(hand)
+    }
+
+    /// Surrender is only offered as the first decision on the original two card hand.
+    pub fn can_surrender(&self, hand: &Hand, hand_count: usize) -> bool {
+        self.surrender != Surrender::None && hand_count == 1 && hand.len() == 2
+    }
+
+    /// Whether `hand` may be split when the player currently holds `hand_count` hands.
+    pub fn can_split(&self, hand: &Hand, hand_count: usize) -> bool {
+        if !hand.can_split() || hand_count >= self.max_split_hands {
+            return false;
+        }
+        let is_resplit_of_aces = hand_count > 1 && hand.cards()[0].rank == Rank::Ace;
+        !is_resplit_of_aces || self.resplit_aces
+    }
+}
+
+impl fmt::Display for TableRules {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.variant {
+            Variant::Classic => {}
+            Variant::Spanish21 => write!(f, "Spanish 21, ")?,
+            Variant::Switch => write!(f, "Blackjack Switch, dealer 22 pushes, ")?,
+        }
+        write!(f, "{} deck(s)", self.decks)?;
+        if self.dealer_hits_soft_17 {
+            write!(f, ", dealer hits soft 17")?;
+        } else {
+            write!(f, ", dealer stands on soft 17")?;
+        }
+        write!(f, ", blackjack pays {}", self.blackjack_pays)?;
+        write!(f, ", double on {}", self.double_on)?;
+        if self.variant.double_any_cards() {
+            write!(f, " (any number of cards)")?;
+        }
+        if !self.double_after_split {
+            write!(f, " (no DAS)")?;
+        }
+        write!(f, ", split to {} hands", self.max_split_hands)?;
+        if self.resplit_aces {
+            write!(f, ", resplit aces")?;
+        }
+        if self.surrender != Surrender::None {
+            write!(f, ", {} surrender", self.surrender)?;
+        }
+        match (self.hole_card, self.original_bets_only) {
+            (HoleCard::American, _) => write!(f, ", dealer peeks for blackjack"),
+            (HoleCard::European, true) => write!(f, ", no hole card (original bets only)"),
+            (HoleCard::European, false) => write!(f, ", no hole card"),
+        }
+    }
+}
+
+fn parse_bool(key: &str, value: &str) -> Result<bool, String> {
+    match value {
+        "true" | "yes" | "on" => Ok(true),
+        "false" | "no" | "off" => Ok(false),
+        _ => Err(format!("{} must be true or false, got '{}'", key, value)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::Suit;
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let cards: Vec<Card> = ranks.iter().map(|&r| Card::new(r, Suit::Clubs)).collect();
+        Hand::from_cards(&cards)
+    }
+
+    #[test]
+    fn test_config_overrides_defaults() {
+        let mut rules = TableRules::default();
+        rules
+            .apply_config(
+                "# Downtown rules\n\
+                 decks = 2\n\
+                 dealer_hits_soft_17 = true   # H17\n\
+                 blackjack_pays = 6:5\n\
+                 double_on = 10-11\n",
+            )
+            .unwrap();
+        assert_eq!(rules.decks, 2);
+        assert!(rules.dealer_hits_soft_17);
+        assert_eq!(rules.blackjack_pays, Payout::SIX_TO_FIVE);
+        assert_eq!(rules.double_on, DoubleOn::TenToEleven);
+        assert_eq!(rules.max_split_hands, 4);
+    }
+
+    #[test]
+    fn test_bad_config_reports_line() {
+        let mut rules = TableRules::default();
+        let err = rules.apply_config("decks = 6\nwild = true").unwrap_err();
+        assert_eq!(err, "line 2: unknown rule 'wild'");
+        assert!(rules.apply_config("decks = 9").is_err());
+    }
+
+    #[test]
+    fn test_cli_flags() {
+        let args: Vec<String> = ["--dealer-hits-soft-17", "yes", "--max-split-hands", "2"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let rules = TableRules::from_args(&args).unwrap();
+        assert!(rules.dealer_hits_soft_17);
+        assert_eq!(rules.max_split_hands, 2);
+    }
+
+    #[test]
+    fn test_dealer_soft_17() {
+        let mut rules = TableRules::default();
+        let soft_17 = hand(&[Rank::Ace, Rank::Six]);
+        assert!(!rules.dealer_should_hit(&soft_17));
+        rules.dealer_hits_soft_17 = true;
+        assert!(rules.dealer_should_hit(&soft_17));
+        assert!(!rules.dealer_should_hit(&hand(&[Rank::King, Rank::Seven])));
+    }
+
+    #[test]
+    fn test_double_and_split_restrictions() {
+        let mut rules = TableRules {
+            double_on: DoubleOn::NineToEleven,
+            ..TableRules::default()
+        };
+        assert!(rules.can_double(&hand(&[Rank::Five, Rank::Five]), 1));
+        assert!(!rules.can_double(&hand(&[Rank::Ace, Rank::Eight]), 1));
+        assert!(!rules.can_double(&hand(&[Rank::Two, Rank::Three, Rank::Five]), 1));
+        rules.double_after_split = false;
+        assert!(!rules.can_double(&hand(&[Rank::Five, Rank::Five]), 2));
+
+        let aces = hand(&[Rank::Ace, Rank::Ace]);
+        assert!(rules.can_split(&aces, 1));
+        assert!(!rules.can_split(&aces, 2));
+        rules.resplit_aces = true;
+        assert!(rules.can_split(&aces, 2));
+        assert!(!rules.can_split(&aces, 4));
+    }
+
+    #[test]
+    fn test_dealer_peeks_only_with_a_hole_card() {
+        let mut rules = TableRules::default();
+        assert!(rules.dealer_peeks(&Card::new(Rank::Ace, Suit::Spades)));
+        assert!(rules.dealer_peeks(&Card::new(Rank::Queen, Suit::Spades)));
+        assert!(!rules.dealer_peeks(&Card::new(Rank::Nine, Suit::Spades)));
+        rules.set("hole_card", "european").unwrap();
+        assert!(!rules.dealer_peeks(&Card::new(Rank::Ace, Suit::Spades)));
+    }
+
+    #[test]
+    fn test_surrender() {
+        let mut rules = TableRules::default();
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        assert!(!rules.can_surrender(&sixteen, 1));
+        rules.set("surrender", "late").unwrap();
+        assert!(rules.can_surrender(&sixteen, 1));
+        assert!(!rules.can_surrender(&sixteen, 2));
+        assert!(!rules.can_surrender(&hand(&[Rank::Ten, Rank::Four, Rank::Two]), 1));
+        assert_eq!(surrender_loss(10), 5);
+        assert_eq!(surrender_loss(5), 3);
+    }
+
+    #[test]
+    fn test_variant_rules() {
+        let mut rules = TableRules::default();
+        rules
+            .apply_config("variant = spanish21\nsurrender = early\n")
+            .unwrap();
+        assert_eq!(rules.variant, Variant::Spanish21);
+        assert!(rules.resplit_aces && rules.hit_split_aces);
+        assert_eq!(rules.surrender, Surrender::Early);
+        assert!(rules.can_double(&hand(&[Rank::Two, Rank::Three, Rank::Five]), 1));
+        assert_eq!(rules.shoe().remaining(), 6 * 48);
+        assert!(rules.set("variant", "pontoon").is_err());
+
+        rules.set("variant", "switch").unwrap();
+        assert_eq!(rules.blackjack_pays, Payout::ONE_TO_ONE);
+        assert_eq!(rules.surrender, Surrender::None);
+        assert!(rules.set("surrender", "early").is_err());
+        assert!(rules.set("surrender", "late").is_ok());
+    }
+
+    #[test]
+    fn test_payout() {
+        assert_eq!(Payout::THREE_TO_TWO.apply(10), 15);
+        assert_eq!(Payout::SIX_TO_FIVE.apply(10), 12);
+        assert!("3-2".parse::<Payout>().is_err());
+    }
+}
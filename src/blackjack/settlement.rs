@@ -0,0 +1,373 @@
+use super::rules::{surrender_loss, Surrender, TableRules};
+use super::Hand;
+use std::fmt;
+
+/// One of the player's hands together with the money riding on it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PlayerHand {
+    pub hand: Hand,
+    /// The bet placed on the hand before any double down.
+    pub bet: u32,
+    pub doubled: bool,
+    /// Given up for half the bet, or for the original bet when a doubled hand is rescued.
+    pub surrendered: bool,
+    /// Which of the player's starting hands this one was dealt as or split from. Only
+    /// Blackjack Switch deals more than one.
+    pub spot: usize,
+    /// Whether the hand's second card came from the other hand in Blackjack Switch.
+    pub switched: bool,
+}
+
+impl PlayerHand {
+    pub fn new(hand: Hand, bet: u32) -> Self {
+        PlayerHand {
+            hand,
+            bet,
+            doubled: false,
+            surrendered: false,
+            spot: 0,
+            switched: false,
+        }
+    }
+
+    /// Splits the pair: the second card moves to a new hand with the same bet and spot.
+    /// Each hand still needs its second card.
+    pub fn split(&mut self) -> PlayerHand {
+        let card = self.hand.pop().expect("cannot split an empty hand");
+        PlayerHand {
+            spot: self.spot,
+            ..PlayerHand::new(Hand::from_cards(&[card]), self.bet)
+        }
+    }
+
+    /// Whether the hand is a blackjack, given how many hands its spot has been split into.
+    /// Only an unsplit two card 21 as dealt counts: a 21 made by switching is an ordinary 21.
+    pub fn is_natural(&self, hand_count: usize) -> bool {
+        hand_count == 1 && !self.switched && self.hand.is_blackjack()
+    }
+
+    /// Everything at risk on this hand: the bet, twice over once it has been doubled.
+    pub fn stake(&self) -> u32 {
+        if self.doubled {
+            self.bet * 2
+        } else {
+            self.bet
+        }
+    }
+}
+
+/// How many hands the spot of `hands[index]` has been split into, which is the hand count
+/// the rules and strategies go by.
+pub fn spot_hands(hands: &[PlayerHand], index: usize) -> usize {
+    hands
+        .iter()
+        .filter(|player| player.spot == hands[index].spot)
+        .count()
+}
+
+/// How a single hand finished against the dealer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Blackjack,
+    /// A 21 paid at a variant's bonus odds.
+    Bonus,
+    Win,
+    Push,
+    Lose,
+    Bust,
+    DealerBlackjack,
+    Surrender,
+    /// A doubled hand given up for the original bet.
+    Rescue,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Outcome::Blackjack => "Blackjack",
+            Outcome::Bonus => "Bonus 21",
+            Outcome::Win => "Win",
+            Outcome::Push => "Push",
+            Outcome::Lose => "Lose",
+            Outcome::Bust => "Bust",
+            Outcome::DealerBlackjack => "Dealer blackjack",
+            Outcome::Surrender => "Surrender",
+            Outcome::Rescue => "Rescue",
+        })
+    }
+}
+
+/// The settlement of one hand: what was at stake, how it ended and the change to the
+/// player's balance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HandResult {
+    pub stake: u32,
+    pub outcome: Outcome,
+    pub net: i32,
+}
+
+/// Settles every player hand against the dealer's finished hand and returns one ledger
+/// entry per hand, in the same order.
+///
+/// A two card 21 only counts as blackjack on an unsplit hand that was not made by
+/// switching. A dealer blackjack beats everything except a player blackjack; with
+/// `original_bets_only` it takes just the original bet of each spot and any doubled or
+/// split stakes are returned.
+///
+/// Under a variant where the dealer's 22 pushes, it only loses to a player blackjack.
+/// Under a variant where a player 21 always wins, a blackjack beats a dealer blackjack and
+/// any other 21 wins unless the dealer has blackjack, at the variant's bonus odds when the
+/// hand qualifies.
+pub fn settle(hands: &[PlayerHand], dealer_hand: &Hand, rules: &TableRules) -> Vec<HandResult> {
+    let dealer_blackjack = dealer_hand.is_blackjack();
+    let dealer_value = dealer_hand.value();
+    hands
+        .iter()
+        .enumerate()
+        .map(|(index, player)| {
+            let stake = player.stake() as i32;
+            let player_blackjack = player.is_natural(spot_hands(hands, index));
+            let first_in_spot = hands[..index].iter().all(|other| other.spot != player.spot);
+            let player_21 = !player.hand.is_bust() && player.hand.value() == 21;
+            let (outcome, net) = if player.surrendered && player.doubled {
+                if dealer_blackjack && rules.surrender == Surrender::Late {
+                    (Outcome::DealerBlackjack, -stake)
+                } else {
+                    (Outcome::Rescue, -(player.bet as i32))
+                }
+            } else if player.surrendered {
+                if dealer_blackjack && rules.surrender == Surrender::Late {
+                    (Outcome::DealerBlackjack, -stake)
+                } else {
+                    (Outcome::Surrender, -(surrender_loss(player.bet) as i32))
+                }
+            } else if player_blackjack && dealer_blackjack && !rules.variant.player_21_always_wins()
+            {
+                (Outcome::Push, 0)
+            } else if player_blackjack {
+                (
+                    Outcome::Blackjack,
+                    rules.blackjack_pays.apply(player.bet) as i32,
+                )
+            } else if dealer_blackjack {
+                let lost = if !rules.original_bets_only {
+                    stake
+                } else if first_in_spot {
+                    player.bet as i32
+                } else {
+                    0
+                };
+                (Outcome::DealerBlackjack, -lost)
+            } else if player.hand.is_bust() {
+                (Outcome::Bust, -stake)
+            } else if player_21 && rules.variant.player_21_always_wins() {
+                match rules.variant.bonus_21(&player.hand) {
+                    Some((_, payout)) if !player.doubled => {
+                        (Outcome::Bonus, payout.apply(player.bet) as i32)
+                    }
+                    _ => (Outcome::Win, stake),
+                }
+            } else if dealer_value == 22 && rules.variant.dealer_22_pushes() {
+                (Outcome::Push, 0)
+            } else if dealer_hand.is_bust() || player.hand.value() > dealer_value {
+                (Outcome::Win, stake)
+            } else if player.hand.value() == dealer_value {
+                (Outcome::Push, 0)
+            } else {
+                (Outcome::Lose, -stake)
+            };
+            HandResult {
+                stake: player.stake(),
+                outcome,
+                net,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Rank, Suit};
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let cards: Vec<Card> = ranks.iter().map(|&r| Card::new(r, Suit::Hearts)).collect();
+        Hand::from_cards(&cards)
+    }
+
+    fn player(ranks: &[Rank], bet: u32, doubled: bool) -> PlayerHand {
+        PlayerHand {
+            doubled,
+            ..PlayerHand::new(hand(ranks), bet)
+        }
+    }
+
+    fn nets(results: &[HandResult]) -> Vec<i32> {
+        results.iter().map(|r| r.net).collect()
+    }
+
+    #[test]
+    fn test_doubling_one_split_hand_only_doubles_that_hand() {
+        let rules = TableRules::default();
+        let hands = [
+            player(&[Rank::Eight, Rank::Three, Rank::King], 10, true),
+            player(&[Rank::Eight, Rank::Ten], 10, false),
+        ];
+        let dealer = hand(&[Rank::Ten, Rank::Nine]);
+        let results = settle(&hands, &dealer, &rules);
+        assert_eq!(results[0].outcome, Outcome::Win);
+        assert_eq!(results[1].outcome, Outcome::Lose);
+        assert_eq!(nets(&results), vec![20, -10]);
+    }
+
+    #[test]
+    fn test_busted_hands_lose_their_own_stake_once() {
+        let rules = TableRules::default();
+        let hands = [
+            player(&[Rank::Six, Rank::Six, Rank::King], 10, true),
+            player(&[Rank::Six, Rank::Ten, Rank::Nine], 10, false),
+        ];
+        let dealer = hand(&[Rank::Ten, Rank::Seven]);
+        let results = settle(&hands, &dealer, &rules);
+        assert!(results.iter().all(|r| r.outcome == Outcome::Bust));
+        assert_eq!(nets(&results), vec![-20, -10]);
+    }
+
+    #[test]
+    fn test_blackjack_payouts() {
+        let mut rules = TableRules::default();
+        let natural = [player(&[Rank::Ace, Rank::King], 10, false)];
+        let dealer = hand(&[Rank::Ten, Rank::Nine]);
+        assert_eq!(nets(&settle(&natural, &dealer, &rules)), vec![15]);
+        rules.set("blackjack_pays", "6:5").unwrap();
+        assert_eq!(nets(&settle(&natural, &dealer, &rules)), vec![12]);
+
+        // A split hand that makes 21 with two cards is an ordinary win.
+        let split = [
+            player(&[Rank::Ace, Rank::King], 10, false),
+            player(&[Rank::Ace, Rank::Two], 10, false),
+        ];
+        let results = settle(&split, &dealer, &rules);
+        assert_eq!(results[0].outcome, Outcome::Win);
+        assert_eq!(results[0].net, 10);
+    }
+
+    #[test]
+    fn test_dealer_blackjack() {
+        let mut rules = TableRules::default();
+        let dealer = hand(&[Rank::Ace, Rank::Queen]);
+        let three_card_21 = [player(&[Rank::Seven, Rank::Seven, Rank::Seven], 10, false)];
+        let results = settle(&three_card_21, &dealer, &rules);
+        assert_eq!(results[0].outcome, Outcome::DealerBlackjack);
+        assert_eq!(results[0].net, -10);
+
+        let natural = [player(&[Rank::Ace, Rank::Jack], 10, false)];
+        assert_eq!(settle(&natural, &dealer, &rules)[0].outcome, Outcome::Push);
+
+        let split_and_doubled = [
+            player(&[Rank::Eight, Rank::Two, Rank::Nine], 10, true),
+            player(&[Rank::Eight, Rank::Ten], 10, false),
+        ];
+        assert_eq!(
+            nets(&settle(&split_and_doubled, &dealer, &rules)),
+            vec![-20, -10]
+        );
+        rules.original_bets_only = true;
+        assert_eq!(
+            nets(&settle(&split_and_doubled, &dealer, &rules)),
+            vec![-10, 0]
+        );
+    }
+
+    #[test]
+    fn test_surrender() {
+        let mut rules = TableRules::default();
+        rules.set("surrender", "late").unwrap();
+        let mut surrendered = player(&[Rank::Ten, Rank::Six], 10, false);
+        surrendered.surrendered = true;
+        let hands = [surrendered];
+        let results = settle(&hands, &hand(&[Rank::Ten, Rank::Seven]), &rules);
+        assert_eq!(results[0].outcome, Outcome::Surrender);
+        assert_eq!(results[0].net, -5);
+
+        // Without a hole card, a late surrender does not stand against a dealer blackjack.
+        let dealer_blackjack = hand(&[Rank::Ten, Rank::Ace]);
+        assert_eq!(nets(&settle(&hands, &dealer_blackjack, &rules)), vec![-10]);
+        rules.set("surrender", "early").unwrap();
+        assert_eq!(nets(&settle(&hands, &dealer_blackjack, &rules)), vec![-5]);
+    }
+
+    #[test]
+    fn test_blackjack_switch() {
+        let mut rules = TableRules::default();
+        rules.set("variant", "switch").unwrap();
+        let natural = PlayerHand {
+            spot: 1,
+            ..player(&[Rank::Ace, Rank::King], 10, false)
+        };
+        let mut split = player(&[Rank::Eight, Rank::Eight], 10, false);
+        let other = PlayerHand {
+            hand: hand(&[Rank::Eight, Rank::Ten]),
+            ..split.split()
+        };
+        split.hand.push(Card::new(Rank::Jack, Suit::Hearts));
+        let hands = [split, other, natural];
+        assert_eq!(spot_hands(&hands, 1), 2);
+        assert_eq!(spot_hands(&hands, 2), 1);
+
+        // The blackjack still counts next to a split spot, and pays 1:1
+        let dealer = hand(&[Rank::Ten, Rank::Seven]);
+        let results = settle(&hands, &dealer, &rules);
+        assert_eq!(results[2].outcome, Outcome::Blackjack);
+        assert_eq!(nets(&results), vec![10, 10, 10]);
+
+        // A dealer 22 pushes everything but the blackjack
+        let dealer_22 = hand(&[Rank::Ten, Rank::Six, Rank::Six]);
+        assert_eq!(nets(&settle(&hands, &dealer_22, &rules)), vec![0, 0, 10]);
+        let dealer_23 = hand(&[Rank::Ten, Rank::Six, Rank::Seven]);
+        assert_eq!(nets(&settle(&hands, &dealer_23, &rules)), vec![10, 10, 10]);
+
+        // An Ace and a ten brought together by switching is just 21, which a dealer 22 pushes
+        let switched = [PlayerHand {
+            switched: true,
+            ..player(&[Rank::Ace, Rank::King], 10, false)
+        }];
+        let results = settle(&switched, &dealer_22, &rules);
+        assert_eq!((results[0].outcome, results[0].net), (Outcome::Push, 0));
+        let results = settle(&switched, &dealer, &rules);
+        assert_eq!((results[0].outcome, results[0].net), (Outcome::Win, 10));
+    }
+
+    #[test]
+    fn test_spanish_21() {
+        let mut rules = TableRules::default();
+        rules.set("variant", "spanish21").unwrap();
+        let dealer_21 = hand(&[Rank::Nine, Rank::Five, Rank::Seven]);
+        let twenty_one = [player(&[Rank::Nine, Rank::Two, Rank::King], 10, false)];
+        let results = settle(&twenty_one, &dealer_21, &rules);
+        assert_eq!((results[0].outcome, results[0].net), (Outcome::Win, 10));
+
+        // Five cards and 6-7-8 pay a bonus, but not once the hand is doubled
+        let five_cards = [player(
+            &[Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Seven],
+            10,
+            false,
+        )];
+        let results = settle(&five_cards, &dealer_21, &rules);
+        assert_eq!((results[0].outcome, results[0].net), (Outcome::Bonus, 15));
+        let suited = [player(&[Rank::Six, Rank::Seven, Rank::Eight], 10, false)];
+        assert_eq!(nets(&settle(&suited, &dealer_21, &rules)), vec![20]);
+        let doubled = [player(&[Rank::Six, Rank::Seven, Rank::Eight], 10, true)];
+        assert_eq!(nets(&settle(&doubled, &dealer_21, &rules)), vec![20]);
+
+        let natural = [player(&[Rank::Ace, Rank::Jack], 10, false)];
+        let dealer_blackjack = hand(&[Rank::Ace, Rank::Queen]);
+        assert_eq!(nets(&settle(&natural, &dealer_blackjack, &rules)), vec![15]);
+
+        // A rescued double only loses the original bet
+        let mut rescued = player(&[Rank::Five, Rank::Six, Rank::Four], 10, true);
+        rescued.surrendered = true;
+        let results = settle(&[rescued], &hand(&[Rank::Ten, Rank::Nine]), &rules);
+        assert_eq!((results[0].outcome, results[0].net), (Outcome::Rescue, -10));
+    }
+}
@@ -0,0 +1,177 @@
+use super::Hand;
+use crate::cards::{Card, Rank, Suit};
+use std::fmt;
+use std::str::FromStr;
+
+/// An optional wager placed next to the main bet and settled on the opening cards alone.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SideBet {
+    /// The player's two cards form a pair.
+    PerfectPairs,
+    /// The player's two cards and the dealer's up card make a three card poker hand.
+    TwentyOnePlusThree,
+    /// The player's two cards total 20.
+    LuckyLadies,
+}
+
+impl SideBet {
+    pub const ALL: [SideBet; 3] = [
+        SideBet::PerfectPairs,
+        SideBet::TwentyOnePlusThree,
+        SideBet::LuckyLadies,
+    ];
+
+    /// The winning hand and what it pays to one, or `None` when the side bet loses.
+    pub fn evaluate(self, player: [Card; 2], up_card: Card) -> Option<(&'static str, u32)> {
+        match self {
+            SideBet::PerfectPairs => perfect_pairs(player),
+            SideBet::TwentyOnePlusThree => twenty_one_plus_three([player[0], player[1], up_card]),
+            SideBet::LuckyLadies => lucky_ladies(player),
+        }
+    }
+
+    /// The change to the player's balance from a side bet of `stake`.
+    pub fn settle(self, stake: u32, player: [Card; 2], up_card: Card) -> i32 {
+        match self.evaluate(player, up_card) {
+            Some((_, pays)) => (stake * pays) as i32,
+            None => -(stake as i32),
+        }
+    }
+}
+
+impl fmt::Display for SideBet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            SideBet::PerfectPairs => "Perfect Pairs",
+            SideBet::TwentyOnePlusThree => "21+3",
+            SideBet::LuckyLadies => "Lucky Ladies",
+        })
+    }
+}
+
+impl FromStr for SideBet {
+    type Err = String;
+
+    /// Accepts the full names or the short forms "pp", "21+3" and "ll", in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s
+            .trim()
+            .to_lowercase()
+            .replace(['-', '_', ' '], "")
+            .as_str()
+        {
+            "pp" | "perfectpairs" => Ok(SideBet::PerfectPairs),
+            "21+3" | "213" => Ok(SideBet::TwentyOnePlusThree),
+            "ll" | "luckyladies" => Ok(SideBet::LuckyLadies),
+            _ => Err(format!("unknown side bet '{}'", s.trim())),
+        }
+    }
+}
+
+/// Perfect pair (same suit) 25:1, coloured pair 12:1, mixed pair 6:1.
+fn perfect_pairs([first, second]: [Card; 2]) -> Option<(&'static str, u32)> {
+    if first.rank != second.rank {
+        None
+    } else if first.suit == second.suit {
+        Some(("Perfect pair", 25))
+    } else if first.suit.is_red() == second.suit.is_red() {
+        Some(("Coloured pair", 12))
+    } else {
+        Some(("Mixed pair", 6))
+    }
+}
+
+/// Suited trips 100:1, straight flush 40:1, three of a kind 30:1, straight 10:1 and
+/// flush 5:1. Aces play high or low in a straight.
+fn twenty_one_plus_three(cards: [Card; 3]) -> Option<(&'static str, u32)> {
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+    let trips = cards.iter().all(|card| card.rank == cards[0].rank);
+    let mut ranks = cards.map(|card| card.rank);
+    ranks.sort();
+    let straight = (ranks[1] as u8 == ranks[0] as u8 + 1 && ranks[2] as u8 == ranks[1] as u8 + 1)
+        || ranks == [Rank::Two, Rank::Three, Rank::Ace];
+    match (trips, straight, flush) {
+        (true, _, true) => Some(("Suited trips", 100)),
+        (_, true, true) => Some(("Straight flush", 40)),
+        (true, _, _) => Some(("Three of a kind", 30)),
+        (_, true, _) => Some(("Straight", 10)),
+        (_, _, true) => Some(("Flush", 5)),
+        _ => None,
+    }
+}
+
+/// Pair of Queen of Hearts 200:1, matched 20 (same rank and suit) 25:1, suited 20 10:1
+/// and any other 20 4:1. The dealer blackjack bonus some tables add is left out, as side
+/// bets are settled before the dealer checks the hole card.
+fn lucky_ladies([first, second]: [Card; 2]) -> Option<(&'static str, u32)> {
+    if Hand::from_cards(&[first, second]).value() != 20 {
+        return None;
+    }
+    let queen_of_hearts = Card::new(Rank::Queen, Suit::Hearts);
+    if first == queen_of_hearts && second == queen_of_hearts {
+        Some(("Pair of Queen of Hearts", 200))
+    } else if first == second {
+        Some(("Matched 20", 25))
+    } else if first.suit == second.suit {
+        Some(("Suited 20", 10))
+    } else {
+        Some(("Any 20", 4))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn card(s: &str) -> Card {
+        s.parse().unwrap()
+    }
+
+    fn pays(bet: SideBet, first: &str, second: &str, up: &str) -> Option<u32> {
+        bet.evaluate([card(first), card(second)], card(up))
+            .map(|(_, pays)| pays)
+    }
+
+    #[test]
+    fn test_perfect_pairs() {
+        let pp = SideBet::PerfectPairs;
+        assert_eq!(pays(pp, "8H", "8H", "2C"), Some(25));
+        assert_eq!(pays(pp, "8H", "8D", "2C"), Some(12));
+        assert_eq!(pays(pp, "8H", "8S", "2C"), Some(6));
+        assert_eq!(pays(pp, "8H", "9H", "2C"), None);
+        assert_eq!(pp.settle(10, [card("KS"), card("KC")], card("2C")), 120);
+        assert_eq!(pp.settle(10, [card("KS"), card("QC")], card("2C")), -10);
+    }
+
+    #[test]
+    fn test_twenty_one_plus_three() {
+        let bet = SideBet::TwentyOnePlusThree;
+        assert_eq!(pays(bet, "7S", "7S", "7S"), Some(100));
+        assert_eq!(pays(bet, "9D", "JD", "TD"), Some(40));
+        assert_eq!(pays(bet, "7S", "7H", "7D"), Some(30));
+        assert_eq!(pays(bet, "AS", "2H", "3D"), Some(10));
+        assert_eq!(pays(bet, "QS", "AH", "KD"), Some(10));
+        assert_eq!(pays(bet, "KS", "AH", "2D"), None);
+        assert_eq!(pays(bet, "2C", "9C", "KC"), Some(5));
+    }
+
+    #[test]
+    fn test_lucky_ladies() {
+        let ll = SideBet::LuckyLadies;
+        assert_eq!(pays(ll, "QH", "QH", "5C"), Some(200));
+        assert_eq!(pays(ll, "KS", "KS", "5C"), Some(25));
+        assert_eq!(pays(ll, "KS", "JS", "5C"), Some(10));
+        assert_eq!(pays(ll, "AS", "9D", "5C"), Some(4));
+        assert_eq!(pays(ll, "KS", "9D", "5C"), None);
+    }
+
+    #[test]
+    fn test_side_bet_names() {
+        for bet in SideBet::ALL {
+            assert_eq!(bet.to_string().parse(), Ok(bet));
+        }
+        assert_eq!("PP".parse(), Ok(SideBet::PerfectPairs));
+        assert_eq!("lucky-ladies".parse(), Ok(SideBet::LuckyLadies));
+        assert!("insurance".parse::<SideBet>().is_err());
+    }
+}
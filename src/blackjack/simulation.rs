@@ -0,0 +1,535 @@
+use super::counting::HI_LO;
+use super::insurance::{insurance_offered, max_insurance, settle_insurance, InsuranceResult};
+use super::rules::{HoleCard, Surrender, TableRules};
+use super::settlement::{settle, spot_hands, PlayerHand};
+use super::strategy::{basic_strategy, Action};
+use super::variant::{should_switch, switch_hands};
+use super::Hand;
+use crate::cards::{Rank, Shoe};
+use std::str::FromStr;
+
+/// The smallest bet a simulated player places. Ten keeps 3:2 and 6:5 payouts, half-bet
+/// surrenders and insurance exact in whole dollars.
+pub const BASE_BET: u32 = 10;
+
+/// A player that makes every decision on its own, for headless play.
+pub trait Strategy {
+    /// The bet for the next round, made before any card is dealt.
+    fn bet(&mut self, _shoe: &Shoe) -> u32 {
+        BASE_BET
+    }
+
+    /// Whether to take insurance (or even money) when the dealer shows an Ace.
+    fn take_insurance(&mut self) -> bool {
+        false
+    }
+
+    /// Whether to swap the second cards of the two hands under Blackjack Switch.
+    fn switch(&mut self, first: &Hand, second: &Hand, _up_card: Rank) -> bool {
+        should_switch(first, second)
+    }
+
+    /// The play for `hand`. Doubling, splitting or surrendering when the rules don't allow
+    /// it is treated as a hit.
+    fn decide(
+        &mut self,
+        hand: &Hand,
+        up_card: Rank,
+        hand_count: usize,
+        rules: &TableRules,
+    ) -> Action;
+}
+
+/// Plays by the basic strategy chart for the table rules.
+pub struct BasicStrategy;
+
+impl Strategy for BasicStrategy {
+    fn decide(
+        &mut self,
+        hand: &Hand,
+        up_card: Rank,
+        hand_count: usize,
+        rules: &TableRules,
+    ) -> Action {
+        basic_strategy(hand, up_card, hand_count, rules)
+    }
+}
+
+/// Plays basic strategy but sizes its bets by the Hi-Lo true count, from one base bet at a
+/// true count of +1 or less up to `spread` base bets, and insures at +3 or more.
+pub struct Counter {
+    pub spread: u32,
+    true_count: f64,
+}
+
+impl Counter {
+    pub fn new(spread: u32) -> Self {
+        Counter {
+            spread: spread.max(1),
+            true_count: 0.0,
+        }
+    }
+}
+
+impl Strategy for Counter {
+    fn bet(&mut self, shoe: &Shoe) -> u32 {
+        let running = HI_LO.running_count(shoe.decks(), shoe.dealt_cards());
+        self.true_count = HI_LO.true_count(running, shoe.remaining());
+        let units = (self.true_count.floor() as i64).clamp(1, self.spread as i64) as u32;
+        units * BASE_BET
+    }
+
+    fn take_insurance(&mut self) -> bool {
+        self.true_count >= 3.0
+    }
+
+    fn decide(
+        &mut self,
+        hand: &Hand,
+        up_card: Rank,
+        hand_count: usize,
+        rules: &TableRules,
+    ) -> Action {
+        basic_strategy(hand, up_card, hand_count, rules)
+    }
+}
+
+/// Never takes a card that could bust the hand: hits 11 or less and stands on anything
+/// else, except that aces are always split.
+pub struct NeverBust;
+
+impl Strategy for NeverBust {
+    fn decide(
+        &mut self,
+        hand: &Hand,
+        _up_card: Rank,
+        hand_count: usize,
+        rules: &TableRules,
+    ) -> Action {
+        if rules.can_split(hand, hand_count) && hand.cards()[0].rank == Rank::Ace {
+            Action::Split
+        } else if hand.hard_total() <= 11 {
+            Action::Hit
+        } else {
+            Action::Stand
+        }
+    }
+}
+
+/// Plays the hand exactly as the dealer would, never doubling or splitting.
+pub struct MimicDealer;
+
+impl Strategy for MimicDealer {
+    fn decide(
+        &mut self,
+        hand: &Hand,
+        _up_card: Rank,
+        _hand_count: usize,
+        rules: &TableRules,
+    ) -> Action {
+        if rules.dealer_should_hit(hand) {
+            Action::Hit
+        } else {
+            Action::Stand
+        }
+    }
+}
+
+/// Plays for big wins: splits every pair, doubles every 8 to 11 and hits anything
+/// below 18.
+pub struct Reckless;
+
+impl Strategy for Reckless {
+    fn decide(
+        &mut self,
+        hand: &Hand,
+        _up_card: Rank,
+        hand_count: usize,
+        rules: &TableRules,
+    ) -> Action {
+        if rules.can_split(hand, hand_count) {
+            Action::Split
+        } else if rules.can_double(hand, hand_count) && (8..=11).contains(&hand.value()) {
+            Action::Double
+        } else if hand.value() < 18 {
+            Action::Hit
+        } else {
+            Action::Stand
+        }
+    }
+}
+
+/// The strategies the simulator can be told to play, by name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrategyKind {
+    Basic,
+    /// Hi-Lo counting with a bet spread of this many base bets.
+    Counting(u32),
+    NeverBust,
+    MimicDealer,
+    Reckless,
+}
+
+impl StrategyKind {
+    pub fn build(self) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Basic => Box::new(BasicStrategy),
+            StrategyKind::Counting(spread) => Box::new(Counter::new(spread)),
+            StrategyKind::NeverBust => Box::new(NeverBust),
+            StrategyKind::MimicDealer => Box::new(MimicDealer),
+            StrategyKind::Reckless => Box::new(Reckless),
+        }
+    }
+}
+
+impl FromStr for StrategyKind {
+    type Err = String;
+
+    /// Accepts "basic", "counting" (or "counter"), "never-bust", "mimic-dealer" or
+    /// "reckless". Counting spreads its bets 1-8 unless told otherwise, as in "counting:12".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase().replace('_', "-");
+        match s.split_once(':') {
+            Some(("counting" | "counter", spread)) => spread
+                .parse()
+                .ok()
+                .filter(|&spread| spread > 0)
+                .map(StrategyKind::Counting)
+                .ok_or_else(|| format!("invalid bet spread '{}'", spread)),
+            _ => match s.as_str() {
+                "basic" => Ok(StrategyKind::Basic),
+                "counting" | "counter" => Ok(StrategyKind::Counting(8)),
+                "never-bust" => Ok(StrategyKind::NeverBust),
+                "mimic-dealer" => Ok(StrategyKind::MimicDealer),
+                "reckless" => Ok(StrategyKind::Reckless),
+                _ => Err(format!("unknown strategy '{}'", s)),
+            },
+        }
+    }
+}
+
+/// How a headless round went: the opening bet on every spot together, the player's net
+/// win or loss including insurance, and how many hands were played after splits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundResult {
+    pub bet: u32,
+    pub net: i32,
+    pub hands: usize,
+}
+
+/// Plays one complete round of `strategy` against the dealer, with the same order of
+/// play as the interactive game. The caller reshuffles the shoe between rounds.
+pub fn play_round(shoe: &mut Shoe, rules: &TableRules, strategy: &mut dyn Strategy) -> RoundResult {
+    let bet = strategy.bet(shoe);
+    let spots = rules.variant.spots();
+    let mut player_hands: Vec<PlayerHand> = (0..spots)
+        .map(|spot| PlayerHand {
+            spot,
+            ..PlayerHand::new(Hand::new(), bet)
+        })
+        .collect();
+    let mut dealer_hand = Hand::new();
+    for round in 0..2 {
+        for player in player_hands.iter_mut() {
+            player.hand.push(shoe.draw());
+        }
+        if round == 0 || rules.hole_card == HoleCard::American {
+            dealer_hand.push(shoe.draw());
+        }
+    }
+    let up_card = dealer_hand.cards()[0];
+    let total_bet = bet * spots as u32;
+
+    let mut insurance = 0;
+    if insurance_offered(&up_card) && strategy.take_insurance() {
+        if spots == 1 && player_hands[0].hand.is_blackjack() {
+            // Even money
+            return RoundResult {
+                bet,
+                net: bet as i32,
+                hands: 1,
+            };
+        }
+        insurance = max_insurance(total_bet);
+    }
+
+    if rules.surrender == Surrender::Early
+        && rules.dealer_peeks(&up_card)
+        && !player_hands[0].hand.is_blackjack()
+    {
+        let action = strategy.decide(&player_hands[0].hand, up_card.rank, 1, rules);
+        player_hands[0].surrendered = action == Action::Surrender;
+    }
+
+    let peeked = !player_hands[0].surrendered && rules.dealer_peeks(&up_card);
+    if !(peeked && dealer_hand.is_blackjack()) {
+        if let [first, second] = player_hands.as_mut_slice() {
+            if rules.variant.can_switch()
+                && strategy.switch(&first.hand, &second.hand, up_card.rank)
+            {
+                switch_hands(first, second);
+            }
+        }
+        play_hands(
+            shoe,
+            rules,
+            strategy,
+            &mut player_hands,
+            up_card.rank,
+            &mut |_, _, _| {},
+        );
+        let finished = player_hands
+            .iter()
+            .all(|player| player.surrendered || player.hand.is_bust());
+        if !finished {
+            while dealer_hand.len() < 2 || rules.dealer_should_hit(&dealer_hand) {
+                dealer_hand.push(shoe.draw());
+            }
+        } else if dealer_hand.len() == 1 {
+            // Still needed to settle insurance or a surrender against a dealer blackjack
+            dealer_hand.push(shoe.draw());
+        }
+    }
+
+    let mut net: i32 = settle(&player_hands, &dealer_hand, rules)
+        .iter()
+        .map(|result| result.net)
+        .sum();
+    net += match settle_insurance(insurance, dealer_hand.is_blackjack()) {
+        InsuranceResult::Won(amount) => amount as i32,
+        InsuranceResult::Lost(amount) => -(amount as i32),
+    };
+    RoundResult {
+        bet: total_bet,
+        net,
+        hands: player_hands.len(),
+    }
+}
+
+/// A step in `play_hands`, reported with the index of the hand it happened to so that
+/// the play can be shown as it goes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HandEvent {
+    /// The hand needs no decision: it is on 21, bust, or split aces that take one card.
+    Finished,
+    /// The strategy's play, before it is made. A play the rules don't allow is taken as a
+    /// hit.
+    Chose(Action),
+    /// The hand after the card drawn for a double down.
+    Doubled,
+}
+
+/// Plays every player hand in turn, splitting into new hands as the strategy asks, and
+/// reports each step to `on_event`. Surrendered hands are skipped.
+pub fn play_hands(
+    shoe: &mut Shoe,
+    rules: &TableRules,
+    strategy: &mut dyn Strategy,
+    player_hands: &mut Vec<PlayerHand>,
+    up_card: Rank,
+    on_event: &mut dyn FnMut(HandEvent, usize, &PlayerHand),
+) {
+    let mut index = 0;
+    while index < player_hands.len() {
+        let hand_count = spot_hands(player_hands, index);
+        let player = &mut player_hands[index];
+        let split_aces = hand_count > 1 && player.hand.cards()[0].rank == Rank::Ace;
+        let one_card_only =
+            split_aces && !rules.hit_split_aces && !rules.can_split(&player.hand, hand_count);
+        if player.surrendered {
+            index += 1;
+            continue;
+        }
+        if player.hand.value() >= 21 || one_card_only {
+            on_event(HandEvent::Finished, index, player);
+            index += 1;
+            continue;
+        }
+        let action = match strategy.decide(&player.hand, up_card, hand_count, rules) {
+            Action::Double if !rules.can_double(&player.hand, hand_count) => Action::Hit,
+            Action::Split if !rules.can_split(&player.hand, hand_count) => Action::Hit,
+            Action::Surrender if !rules.can_surrender(&player.hand, hand_count) => Action::Hit,
+            action => action,
+        };
+        on_event(HandEvent::Chose(action), index, player);
+        match action {
+            Action::Stand => index += 1,
+            Action::Hit => player.hand.push(shoe.draw()),
+            Action::Double => {
+                player.doubled = true;
+                player.hand.push(shoe.draw());
+                on_event(HandEvent::Doubled, index, player);
+                index += 1;
+            }
+            Action::Split => {
+                let mut new_hand = player.split();
+                new_hand.hand.push(shoe.draw());
+                player.hand.push(shoe.draw());
+                player_hands.insert(index + 1, new_hand);
+            }
+            Action::Surrender => {
+                player.surrendered = true;
+                index += 1;
+            }
+        }
+    }
+}
+
+/// Running totals over many simulated rounds, kept in base bets.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Stats {
+    pub rounds: u64,
+    pub hands: u64,
+    /// Sum of the opening bets.
+    pub wagered: f64,
+    /// Sum and sum of squares of the net result per round.
+    pub net: f64,
+    pub net_squared: f64,
+}
+
+impl Stats {
+    pub fn record(&mut self, result: RoundResult) {
+        let bet = result.bet as f64 / BASE_BET as f64;
+        let net = result.net as f64 / BASE_BET as f64;
+        self.rounds += 1;
+        self.hands += result.hands as u64;
+        self.wagered += bet;
+        self.net += net;
+        self.net_squared += net * net;
+    }
+
+    pub fn merge(&mut self, other: &Stats) {
+        self.rounds += other.rounds;
+        self.hands += other.hands;
+        self.wagered += other.wagered;
+        self.net += other.net;
+        self.net_squared += other.net_squared;
+    }
+
+    /// The average result of a round, in base bets.
+    pub fn ev_per_round(&self) -> f64 {
+        self.net / self.rounds as f64
+    }
+
+    /// The average result as a share of the money bet, negative when the house wins.
+    pub fn ev_per_bet(&self) -> f64 {
+        self.net / self.wagered
+    }
+
+    /// The variance of a round's result, in base bets squared.
+    pub fn variance(&self) -> f64 {
+        let mean = self.ev_per_round();
+        self.net_squared / self.rounds as f64 - mean * mean
+    }
+
+    /// The standard error of `ev_per_round`.
+    pub fn standard_error(&self) -> f64 {
+        (self.variance() / self.rounds as f64).sqrt()
+    }
+
+    /// The chance of ever losing a bankroll of `bankroll` base bets, from the usual
+    /// exp(-2 x EV x bankroll / variance) approximation. Certain ruin without an edge.
+    pub fn risk_of_ruin(&self, bankroll: f64) -> f64 {
+        let ev = self.ev_per_round();
+        if ev <= 0.0 {
+            1.0
+        } else {
+            (-2.0 * ev * bankroll / self.variance()).exp()
+        }
+    }
+}
+
+/// Plays `rounds` rounds from a fresh shoe, reshuffling at the cut card.
+pub fn simulate(rules: &TableRules, kind: StrategyKind, rounds: u64) -> Stats {
+    let mut shoe = rules.shoe();
+    let mut strategy = kind.build();
+    let mut stats = Stats::default();
+    for _ in 0..rounds {
+        if shoe.cut_card_reached() {
+            shoe.reshuffle();
+        }
+        stats.record(play_round(&mut shoe, rules, strategy.as_mut()));
+    }
+    stats
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Suit};
+
+    fn shoe(ranks: &[Rank]) -> Shoe {
+        let cards = ranks.iter().map(|&r| Card::new(r, Suit::Spades)).collect();
+        Shoe::from_cards(cards, TableRules::default().shoe())
+    }
+
+    #[test]
+    fn test_round_with_split_and_double() {
+        let rules = TableRules::default();
+        // Player 8 8, dealer 6 10. The split off eight draws 3 and the first a Queen, which
+        // stands, then 8 3 doubles onto a King and the dealer busts with a nine.
+        let mut shoe = shoe(&[
+            Rank::Eight,
+            Rank::Six,
+            Rank::Eight,
+            Rank::Ten,
+            Rank::Three,
+            Rank::Queen,
+            Rank::King,
+            Rank::Nine,
+        ]);
+        let result = play_round(&mut shoe, &rules, &mut BasicStrategy);
+        assert_eq!(result.hands, 2);
+        assert_eq!(result.net, 30);
+        assert_eq!(shoe.remaining(), 0);
+    }
+
+    #[test]
+    fn test_dealer_blackjack_ends_the_round() {
+        let rules = TableRules::default();
+        let mut shoe = shoe(&[Rank::Ten, Rank::Ace, Rank::Six, Rank::King, Rank::Two]);
+        let result = play_round(&mut shoe, &rules, &mut BasicStrategy);
+        assert_eq!(result.net, -10);
+        assert_eq!(shoe.remaining(), 1);
+    }
+
+    #[test]
+    fn test_switch_round() {
+        let mut rules = TableRules::default();
+        rules.set("variant", "switch").unwrap();
+        // K 6 and 9 K against a ten switch into K K and 9 6, which hits to 21 with a six,
+        // and both beat the dealer's 19
+        let mut shoe = shoe(&[
+            Rank::King,
+            Rank::Nine,
+            Rank::Ten,
+            Rank::Six,
+            Rank::King,
+            Rank::Nine,
+            Rank::Six,
+        ]);
+        let result = play_round(&mut shoe, &rules, &mut BasicStrategy);
+        assert_eq!((result.bet, result.net, result.hands), (20, 20, 2));
+        assert_eq!(shoe.remaining(), 0);
+    }
+
+    #[test]
+    fn test_strategy_names() {
+        assert_eq!("basic".parse(), Ok(StrategyKind::Basic));
+        assert_eq!("counting".parse(), Ok(StrategyKind::Counting(8)));
+        assert_eq!("counting:12".parse(), Ok(StrategyKind::Counting(12)));
+        assert_eq!("never_bust".parse(), Ok(StrategyKind::NeverBust));
+        assert_eq!("counter".parse(), Ok(StrategyKind::Counting(8)));
+        assert_eq!("reckless".parse(), Ok(StrategyKind::Reckless));
+        assert!("counting:0".parse::<StrategyKind>().is_err());
+        assert!("martingale".parse::<StrategyKind>().is_err());
+    }
+
+    #[test]
+    fn test_basic_strategy_house_edge_is_small() {
+        let stats = simulate(&TableRules::default(), StrategyKind::Basic, 20_000);
+        assert_eq!(stats.rounds, 20_000);
+        assert!(stats.ev_per_bet().abs() < 0.05, "{}", stats.ev_per_bet());
+        assert!(stats.variance() > 1.0 && stats.variance() < 1.6);
+    }
+}
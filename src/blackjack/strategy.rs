@@ -0,0 +1,267 @@
+use super::rules::{Surrender, TableRules};
+use super::{card_value, Hand};
+use crate::cards::Rank;
+use std::fmt;
+
+/// A decision the player can make on a hand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Action::Hit => "Hit",
+            Action::Stand => "Stand",
+            Action::Double => "Double down",
+            Action::Split => "Split",
+            Action::Surrender => "Surrender",
+        })
+    }
+}
+
+/// A basic strategy chart entry, before checking what the table actually allows.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Play {
+    H,
+    S,
+    /// Double if allowed, otherwise hit.
+    Dh,
+    /// Double if allowed, otherwise stand.
+    Ds,
+    P,
+    /// Split if doubling after a split is allowed, otherwise hit.
+    Ph,
+}
+
+/// The basic strategy play for `hand` against the dealer's up card, for a multi-deck shoe
+/// under the given rules. `hand_count` is how many hands the player holds, which decides
+/// whether splitting, doubling and surrendering are still allowed.
+pub fn basic_strategy(hand: &Hand, up_card: Rank, hand_count: usize, rules: &TableRules) -> Action {
+    let up = dealer_value(up_card);
+    let h17 = rules.dealer_hits_soft_17;
+
+    if rules.can_surrender(hand, hand_count) && should_surrender(hand, up, rules) {
+        return Action::Surrender;
+    }
+
+    if rules.can_split(hand, hand_count) {
+        let pair = pair_play(hand.cards()[0].rank, up);
+        if pair == Some(Play::P) || (pair == Some(Play::Ph) && rules.double_after_split) {
+            return Action::Split;
+        }
+    }
+
+    let play = if hand.is_soft() {
+        soft_play(hand.value(), up, h17)
+    } else {
+        hard_play(hand.value(), up, h17)
+    };
+    match play {
+        Play::S => Action::Stand,
+        Play::Dh | Play::Ds if rules.can_double(hand, hand_count) => Action::Double,
+        Play::Ds => Action::Stand,
+        _ => Action::Hit,
+    }
+}
+
+/// The dealer's up card as 2 to 11, with the Ace high.
+fn dealer_value(up_card: Rank) -> u8 {
+    if up_card == Rank::Ace {
+        11
+    } else {
+        card_value(up_card)
+    }
+}
+
+fn hard_play(total: u8, up: u8, h17: bool) -> Play {
+    match total {
+        17.. => Play::S,
+        13..=16 if up <= 6 => Play::S,
+        12 if (4..=6).contains(&up) => Play::S,
+        11 if up <= 10 || h17 => Play::Dh,
+        10 if up <= 9 => Play::Dh,
+        9 if (3..=6).contains(&up) => Play::Dh,
+        _ => Play::H,
+    }
+}
+
+fn soft_play(total: u8, up: u8, h17: bool) -> Play {
+    match total {
+        20.. => Play::S,
+        19 if up == 6 && h17 => Play::Ds,
+        19 => Play::S,
+        18 if up == 2 && h17 => Play::Ds,
+        18 if (3..=6).contains(&up) => Play::Ds,
+        18 if up <= 8 => Play::S,
+        17 if (3..=6).contains(&up) => Play::Dh,
+        15 | 16 if (4..=6).contains(&up) => Play::Dh,
+        13 | 14 if (5..=6).contains(&up) => Play::Dh,
+        _ => Play::H,
+    }
+}
+
+/// The play for a pair, or `None` when the pair should be played as an ordinary total.
+fn pair_play(rank: Rank, up: u8) -> Option<Play> {
+    match card_value(rank) {
+        1 | 8 => Some(Play::P),
+        9 if up <= 9 && up != 7 => Some(Play::P),
+        7 if up <= 7 => Some(Play::P),
+        6 if up == 2 => Some(Play::Ph),
+        6 if up <= 6 => Some(Play::P),
+        4 if (5..=6).contains(&up) => Some(Play::Ph),
+        2 | 3 if up <= 3 => Some(Play::Ph),
+        2 | 3 if up <= 7 => Some(Play::P),
+        _ => None,
+    }
+}
+
+fn should_surrender(hand: &Hand, up: u8, rules: &TableRules) -> bool {
+    if hand.is_soft() {
+        return false;
+    }
+    let total = hand.value();
+    let pair = if hand.can_split() {
+        Some(card_value(hand.cards()[0].rank))
+    } else {
+        None
+    };
+    match rules.surrender {
+        Surrender::None => false,
+        Surrender::Late => {
+            let h17 = rules.dealer_hits_soft_17;
+            match (total, up) {
+                (16, 9..=11) if pair != Some(8) => true,
+                (16, 11) => h17,
+                (15, 10) => true,
+                (15, 11) | (17, 11) => h17,
+                _ => false,
+            }
+        }
+        Surrender::Early => matches!((total, up), (5..=7 | 12..=17, 11) | (14..=16, 10)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{Card, Suit};
+
+    fn hand(ranks: &[Rank]) -> Hand {
+        let cards: Vec<Card> = ranks
+            .iter()
+            .map(|&r| Card::new(r, Suit::Diamonds))
+            .collect();
+        Hand::from_cards(&cards)
+    }
+
+    #[test]
+    fn test_hard_totals() {
+        let rules = TableRules::default();
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        assert_eq!(
+            basic_strategy(&sixteen, Rank::Six, 1, &rules),
+            Action::Stand
+        );
+        assert_eq!(
+            basic_strategy(&sixteen, Rank::Seven, 1, &rules),
+            Action::Hit
+        );
+        let twelve = hand(&[Rank::Ten, Rank::Two]);
+        assert_eq!(basic_strategy(&twelve, Rank::Three, 1, &rules), Action::Hit);
+        assert_eq!(
+            basic_strategy(&twelve, Rank::Four, 1, &rules),
+            Action::Stand
+        );
+        let eleven = hand(&[Rank::Six, Rank::Five]);
+        assert_eq!(
+            basic_strategy(&eleven, Rank::King, 1, &rules),
+            Action::Double
+        );
+        assert_eq!(basic_strategy(&eleven, Rank::Ace, 1, &rules), Action::Hit);
+    }
+
+    #[test]
+    fn test_soft_totals_and_double_fallbacks() {
+        let rules = TableRules::default();
+        let soft_18 = hand(&[Rank::Ace, Rank::Seven]);
+        assert_eq!(
+            basic_strategy(&soft_18, Rank::Four, 1, &rules),
+            Action::Double
+        );
+        assert_eq!(
+            basic_strategy(&soft_18, Rank::Eight, 1, &rules),
+            Action::Stand
+        );
+        assert_eq!(basic_strategy(&soft_18, Rank::Nine, 1, &rules), Action::Hit);
+        // Three cards can no longer be doubled, so soft 18 stands and 11 hits.
+        let three_card_soft_18 = hand(&[Rank::Ace, Rank::Four, Rank::Three]);
+        assert_eq!(
+            basic_strategy(&three_card_soft_18, Rank::Four, 1, &rules),
+            Action::Stand
+        );
+        let three_card_11 = hand(&[Rank::Two, Rank::Four, Rank::Five]);
+        assert_eq!(
+            basic_strategy(&three_card_11, Rank::Six, 1, &rules),
+            Action::Hit
+        );
+    }
+
+    #[test]
+    fn test_pairs_respect_rules() {
+        let mut rules = TableRules::default();
+        let eights = hand(&[Rank::Eight, Rank::Eight]);
+        assert_eq!(basic_strategy(&eights, Rank::Ten, 1, &rules), Action::Split);
+        let tens = hand(&[Rank::King, Rank::King]);
+        assert_eq!(basic_strategy(&tens, Rank::Six, 1, &rules), Action::Stand);
+        let twos = hand(&[Rank::Two, Rank::Two]);
+        assert_eq!(basic_strategy(&twos, Rank::Two, 1, &rules), Action::Split);
+        rules.double_after_split = false;
+        assert_eq!(basic_strategy(&twos, Rank::Two, 1, &rules), Action::Hit);
+        // Once no more hands are allowed, eights are played as a hard 16.
+        assert_eq!(basic_strategy(&eights, Rank::Ten, 4, &rules), Action::Hit);
+    }
+
+    #[test]
+    fn test_dealer_hits_soft_17_changes() {
+        let mut rules = TableRules::default();
+        let eleven = hand(&[Rank::Six, Rank::Five]);
+        let soft_19 = hand(&[Rank::Ace, Rank::Eight]);
+        assert_eq!(
+            basic_strategy(&soft_19, Rank::Six, 1, &rules),
+            Action::Stand
+        );
+        rules.dealer_hits_soft_17 = true;
+        assert_eq!(
+            basic_strategy(&eleven, Rank::Ace, 1, &rules),
+            Action::Double
+        );
+        assert_eq!(
+            basic_strategy(&soft_19, Rank::Six, 1, &rules),
+            Action::Double
+        );
+    }
+
+    #[test]
+    fn test_surrender() {
+        let mut rules = TableRules::default();
+        let sixteen = hand(&[Rank::Ten, Rank::Six]);
+        assert_eq!(basic_strategy(&sixteen, Rank::Ten, 1, &rules), Action::Hit);
+        rules.surrender = Surrender::Late;
+        assert_eq!(
+            basic_strategy(&sixteen, Rank::Ten, 1, &rules),
+            Action::Surrender
+        );
+        assert_eq!(
+            basic_strategy(&hand(&[Rank::Eight, Rank::Eight]), Rank::Ten, 1, &rules),
+            Action::Split
+        );
+        // After a split the hand can no longer be surrendered.
+        assert_eq!(basic_strategy(&sixteen, Rank::Ten, 2, &rules), Action::Hit);
+    }
+}
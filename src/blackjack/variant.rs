@@ -0,0 +1,260 @@
+use super::rules::Payout;
+use super::settlement::PlayerHand;
+use super::Hand;
+use crate::cards::{create_deck, Card, Rank, Suit};
+use std::fmt;
+use std::str::FromStr;
+
+/// Which game the blackjack engine is dealing. A variant changes the deck, the plays on
+/// offer and the settlement through the hooks below; everything else plays as blackjack.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Variant {
+    Classic,
+    /// Spanish 21: no tens in the deck, but a player 21 always wins, long 21s and 6-7-8 /
+    /// 7-7-7 pay a bonus, doubles are allowed on any number of cards and a double can be
+    /// rescued.
+    Spanish21,
+    /// Blackjack Switch: two hands each, whose second cards may be swapped, paid for by a
+    /// dealer 22 pushing and blackjack paying 1:1.
+    Switch,
+}
+
+impl Variant {
+    /// One deck of the game. Spanish 21 takes out the four tens, leaving 48 cards with the
+    /// jacks, queens and kings still in.
+    pub fn deck(self) -> Vec<Card> {
+        let mut deck = create_deck();
+        if self == Variant::Spanish21 {
+            deck.retain(|card| card.rank != Rank::Ten);
+        }
+        deck
+    }
+
+    /// How many hands each player is dealt, each with the same bet.
+    pub fn spots(self) -> usize {
+        match self {
+            Variant::Switch => 2,
+            _ => 1,
+        }
+    }
+
+    /// Whether the player may swap the second cards of their two hands.
+    pub fn can_switch(self) -> bool {
+        self == Variant::Switch
+    }
+
+    /// Whether a dealer who busts with exactly 22 pushes every hand but a blackjack.
+    pub fn dealer_22_pushes(self) -> bool {
+        self == Variant::Switch
+    }
+
+    /// Whether a player's 21 wins even when the dealer also makes 21.
+    pub fn player_21_always_wins(self) -> bool {
+        self == Variant::Spanish21
+    }
+
+    /// Whether a hand may be doubled after the first two cards.
+    pub fn double_any_cards(self) -> bool {
+        self == Variant::Spanish21
+    }
+
+    /// Whether a doubled hand may be given up after its card is dealt, forfeiting the
+    /// original bet and taking back the double.
+    pub fn double_down_rescue(self) -> bool {
+        self == Variant::Spanish21
+    }
+
+    /// The bonus a 21 on `hand` pays instead of even money, if any. Doubled hands only
+    /// ever get even money.
+    ///
+    /// In Spanish 21 a five card 21 pays 3:2, six cards 2:1 and seven or more 3:1, and
+    /// 6-7-8 or 7-7-7 pays 3:2 in mixed suits, 2:1 suited and 3:1 in spades. The casino
+    /// jackpot for suited 7-7-7 against a dealer 7 is left out.
+    pub fn bonus_21(self, hand: &Hand) -> Option<(&'static str, Payout)> {
+        if self != Variant::Spanish21 || hand.value() != 21 {
+            return None;
+        }
+        let cards = hand.cards();
+        let by_suit = |name| {
+            if cards.iter().all(|card| card.suit == Suit::Spades) {
+                Some((name, payout(3, 1)))
+            } else if cards.iter().all(|card| card.suit == cards[0].suit) {
+                Some((name, payout(2, 1)))
+            } else {
+                Some((name, Payout::THREE_TO_TWO))
+            }
+        };
+        let mut ranks: Vec<Rank> = cards.iter().map(|card| card.rank).collect();
+        ranks.sort();
+        match ranks.as_slice() {
+            [Rank::Six, Rank::Seven, Rank::Eight] => by_suit("6-7-8"),
+            [Rank::Seven, Rank::Seven, Rank::Seven] => by_suit("7-7-7"),
+            _ => match cards.len() {
+                5 => Some(("Five card 21", Payout::THREE_TO_TWO)),
+                6 => Some(("Six card 21", payout(2, 1))),
+                n if n >= 7 => Some(("Seven card 21", payout(3, 1))),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Swaps the second cards of two freshly dealt hands, as in Blackjack Switch.
+pub fn switch_cards(first: &mut Hand, second: &mut Hand) {
+    let (a, b) = (first.cards()[1], second.cards()[1]);
+    *first = Hand::from_cards(&[first.cards()[0], b]);
+    *second = Hand::from_cards(&[second.cards()[0], a]);
+}
+
+/// Switches two of a player's hands and marks them, so that a 21 made this way is not
+/// paid as a blackjack.
+pub fn switch_hands(first: &mut PlayerHand, second: &mut PlayerHand) {
+    switch_cards(&mut first.hand, &mut second.hand);
+    first.switched = true;
+    second.switched = true;
+}
+
+/// A rule of thumb for Blackjack Switch: switch when the two swapped hands are worth more
+/// together than the two dealt ones.
+pub fn should_switch(first: &Hand, second: &Hand) -> bool {
+    let (mut switched_first, mut switched_second) = (first.clone(), second.clone());
+    switch_cards(&mut switched_first, &mut switched_second);
+    rough_value(&switched_first, true) + rough_value(&switched_second, true)
+        > rough_value(first, false) + rough_value(second, false)
+}
+
+/// Roughly what a two card hand is worth to the player in Blackjack Switch, in bets and
+/// averaged over the dealer's up cards. Only meant for ranking hands against each other.
+/// A 21 made by `switched` cards is no blackjack, so it pushes a dealer 22 and ties a
+/// dealer 21.
+fn rough_value(hand: &Hand, switched: bool) -> f64 {
+    let cards = hand.cards();
+    if hand.is_blackjack() && !switched {
+        return 0.9;
+    }
+    if cards[0].rank == cards[1].rank {
+        match cards[0].rank {
+            Rank::Ace => return 0.4,
+            Rank::Eight => return -0.1,
+            _ => {}
+        }
+    }
+    match (hand.is_soft(), hand.value()) {
+        (true, 13..=18) => 0.0,
+        (_, 21) => 0.8,
+        (_, 20) => 0.55,
+        (_, 19) => 0.3,
+        (_, 18) => 0.1,
+        (_, 17) => -0.15,
+        (_, 12..=16) => -0.3,
+        (_, 11) => 0.25,
+        (_, 10) => 0.2,
+        (_, 9) => 0.05,
+        _ => -0.1,
+    }
+}
+
+fn payout(numerator: u32, denominator: u32) -> Payout {
+    Payout {
+        numerator,
+        denominator,
+    }
+}
+
+impl fmt::Display for Variant {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Variant::Classic => "classic",
+            Variant::Spanish21 => "spanish21",
+            Variant::Switch => "switch",
+        })
+    }
+}
+
+impl FromStr for Variant {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "classic" | "blackjack" => Ok(Variant::Classic),
+            "spanish21" | "spanish-21" | "spanish" => Ok(Variant::Spanish21),
+            "switch" | "blackjack-switch" => Ok(Variant::Switch),
+            _ => Err(format!(
+                "variant must be classic, spanish21 or switch, got '{}'",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hand(cards: &[&str]) -> Hand {
+        let cards: Vec<Card> = cards.iter().map(|s| s.parse().unwrap()).collect();
+        Hand::from_cards(&cards)
+    }
+
+    fn bonus(cards: &[&str]) -> Option<Payout> {
+        Variant::Spanish21
+            .bonus_21(&hand(cards))
+            .map(|(_, payout)| payout)
+    }
+
+    #[test]
+    fn test_spanish_deck_has_no_tens() {
+        let deck = Variant::Spanish21.deck();
+        assert_eq!(deck.len(), 48);
+        assert!(deck.iter().all(|card| card.rank != Rank::Ten));
+        assert_eq!(
+            deck.iter().filter(|card| card.rank.is_ten_value()).count(),
+            12
+        );
+        assert_eq!(Variant::Classic.deck(), create_deck());
+    }
+
+    #[test]
+    fn test_bonus_21() {
+        assert_eq!(bonus(&["6H", "7C", "8D"]), Some(Payout::THREE_TO_TWO));
+        assert_eq!(bonus(&["8H", "6H", "7H"]), Some(payout(2, 1)));
+        assert_eq!(bonus(&["7S", "7S", "7S"]), Some(payout(3, 1)));
+        assert_eq!(
+            bonus(&["2H", "3C", "4D", "5S", "7H"]),
+            Some(Payout::THREE_TO_TWO)
+        );
+        assert_eq!(
+            bonus(&["AH", "2C", "3D", "4S", "5H", "6C"]),
+            Some(payout(2, 1))
+        );
+        assert_eq!(
+            bonus(&["AH", "AC", "2D", "2S", "3H", "4C", "8D"]),
+            Some(payout(3, 1))
+        );
+        assert_eq!(bonus(&["9H", "5C", "7D"]), None);
+        assert_eq!(bonus(&["2H", "3C", "4D", "5S", "6H"]), None);
+        assert_eq!(Variant::Classic.bonus_21(&hand(&["6H", "7C", "8D"])), None);
+    }
+
+    #[test]
+    fn test_switch() {
+        let (mut first, mut second) = (hand(&["KC", "6S"]), hand(&["9D", "KH"]));
+        assert!(should_switch(&first, &second));
+        switch_cards(&mut first, &mut second);
+        assert_eq!((first.value(), second.value()), (20, 15));
+        assert!(!should_switch(&first, &second));
+
+        // Two blackjacks stay as they are, but one is worth breaking up a twenty for
+        assert!(!should_switch(&hand(&["AS", "KD"]), &hand(&["QC", "AH"])));
+        // A 21 made by switching is only an ordinary 21, not worth giving up a twenty for
+        assert!(!should_switch(&hand(&["AS", "5D"]), &hand(&["QC", "JH"])));
+        assert!(should_switch(&hand(&["AS", "6D"]), &hand(&["5C", "JH"])));
+
+        let mut first = PlayerHand::new(hand(&["AS", "5D"]), 10);
+        let mut second = PlayerHand::new(hand(&["QC", "KH"]), 10);
+        switch_hands(&mut first, &mut second);
+        assert!(first.hand.is_blackjack());
+        assert!(!first.is_natural(1));
+        assert!(second.switched);
+    }
+}
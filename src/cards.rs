@@ -0,0 +1,522 @@
+use colored::*;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::fmt;
+use std::fs;
+use std::str::FromStr;
+
+/// The four suits of a standard French deck.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Suit {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    /// Hearts and Diamonds are red, Clubs and Spades are black.
+    pub fn is_red(self) -> bool {
+        matches!(self, Suit::Hearts | Suit::Diamonds)
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Suit::Hearts => "Hearts",
+            Suit::Diamonds => "Diamonds",
+            Suit::Clubs => "Clubs",
+            Suit::Spades => "Spades",
+        }
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Suit {
+    type Err = ParseCardError;
+
+    /// Accepts the full name ("Hearts") or its first letter ("h"), in any case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "hearts" | "h" => Ok(Suit::Hearts),
+            "diamonds" | "d" => Ok(Suit::Diamonds),
+            "clubs" | "c" => Ok(Suit::Clubs),
+            "spades" | "s" => Ok(Suit::Spades),
+            _ => Err(ParseCardError(format!("unknown suit '{}'", s.trim()))),
+        }
+    }
+}
+
+/// Card ranks, ordered from Two (lowest) to Ace (highest).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "Jack",
+            Rank::Queen => "Queen",
+            Rank::King => "King",
+            Rank::Ace => "Ace",
+        }
+    }
+
+    /// True for 10, Jack, Queen and King.
+    pub fn is_ten_value(self) -> bool {
+        matches!(self, Rank::Ten | Rank::Jack | Rank::Queen | Rank::King)
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Rank {
+    type Err = ParseCardError;
+
+    /// Accepts "2".."10", the full face names, or the short forms "T", "J", "Q", "K" and "A".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "2" => Ok(Rank::Two),
+            "3" => Ok(Rank::Three),
+            "4" => Ok(Rank::Four),
+            "5" => Ok(Rank::Five),
+            "6" => Ok(Rank::Six),
+            "7" => Ok(Rank::Seven),
+            "8" => Ok(Rank::Eight),
+            "9" => Ok(Rank::Nine),
+            "10" | "t" => Ok(Rank::Ten),
+            "jack" | "j" => Ok(Rank::Jack),
+            "queen" | "q" => Ok(Rank::Queen),
+            "king" | "k" => Ok(Rank::King),
+            "ace" | "a" => Ok(Rank::Ace),
+            _ => Err(ParseCardError(format!("unknown rank '{}'", s.trim()))),
+        }
+    }
+}
+
+/// A single playing card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Card {
+    pub rank: Rank,
+    pub suit: Suit,
+}
+
+impl Card {
+    pub fn new(rank: Rank, suit: Suit) -> Self {
+        Card { rank, suit }
+    }
+}
+
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} of {}", self.rank, self.suit)
+    }
+}
+
+impl FromStr for Card {
+    type Err = ParseCardError;
+
+    /// Parses the same "Rank of Suit" form that `Display` produces, e.g. "Queen of Hearts",
+    /// or the short form of a rank followed by a suit letter, e.g. "QH" or "10d".
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parts = s.split_whitespace();
+        match (parts.next(), parts.next(), parts.next(), parts.next()) {
+            (Some(rank), Some(of), Some(suit), None) if of.eq_ignore_ascii_case("of") => {
+                Ok(Card::new(rank.parse()?, suit.parse()?))
+            }
+            (Some(short), None, None, None) if short.len() >= 2 && short.is_ascii() => {
+                let (rank, suit) = short.split_at(short.len() - 1);
+                Ok(Card::new(rank.parse()?, suit.parse()?))
+            }
+            _ => Err(ParseCardError(format!(
+                "expected a card like 'Queen of Hearts' or 'QH', got '{}'",
+                s.trim()
+            ))),
+        }
+    }
+}
+
+/// Error returned when a rank, suit or card cannot be parsed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseCardError(pub String);
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+/// Builds a single, unshuffled 52-card deck.
+pub fn create_deck() -> Vec<Card> {
+    let mut deck = Vec::with_capacity(52);
+    for &suit in &Suit::ALL {
+        for &rank in &Rank::ALL {
+            deck.push(Card::new(rank, suit));
+        }
+    }
+    deck
+}
+
+/// Reads a list of cards in dealing order, either as a JSON array of strings
+/// (`["Ace of Spades", "KH"]`) or as plain text with one or more comma separated cards per
+/// line. In plain text, anything after a `#` is a comment.
+pub fn parse_deck(contents: &str) -> Result<Vec<Card>, ParseCardError> {
+    if contents.trim_start().starts_with('[') {
+        return parse_json_deck(contents);
+    }
+    let mut cards = Vec::new();
+    for (line_number, line) in contents.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or("");
+        for card in line.split(',').filter(|card| !card.trim().is_empty()) {
+            let card = card
+                .parse()
+                .map_err(|e| ParseCardError(format!("line {}: {}", line_number + 1, e)))?;
+            cards.push(card);
+        }
+    }
+    Ok(cards)
+}
+
+/// Parses a JSON array of card strings. Only what a deck file needs is supported: plain
+/// strings without escapes, separated by commas.
+fn parse_json_deck(contents: &str) -> Result<Vec<Card>, ParseCardError> {
+    let body = contents
+        .trim()
+        .strip_prefix('[')
+        .and_then(|rest| rest.strip_suffix(']'))
+        .ok_or_else(|| ParseCardError("expected a JSON array of cards".to_string()))?;
+    let mut cards = Vec::new();
+    let mut rest = body.trim();
+    while !rest.is_empty() {
+        let quoted = rest
+            .strip_prefix('"')
+            .and_then(|rest| rest.split_once('"'))
+            .ok_or_else(|| {
+                ParseCardError(format!("card {}: expected a string", cards.len() + 1))
+            })?;
+        let card = quoted
+            .0
+            .parse()
+            .map_err(|e| ParseCardError(format!("card {}: {}", cards.len() + 1, e)))?;
+        cards.push(card);
+        rest = quoted.1.trim_start();
+        if let Some(next) = rest.strip_prefix(',') {
+            rest = next.trim_start();
+        } else if !rest.is_empty() {
+            return Err(ParseCardError(format!(
+                "card {}: expected ',' or ']'",
+                cards.len()
+            )));
+        }
+    }
+    Ok(cards)
+}
+
+/// Reads a deck file, see `parse_deck` for the formats.
+pub fn load_deck(path: &str) -> Result<Vec<Card>, String> {
+    let contents =
+        fs::read_to_string(path).map_err(|e| format!("could not read {}: {}", path, e))?;
+    parse_deck(&contents).map_err(|e| format!("{}: {}", path, e))
+}
+
+/// The most decks a shoe may hold.
+pub const MAX_DECKS: usize = 8;
+
+/// The share of the shoe dealt before the cut card comes out, unless configured otherwise.
+pub const DEFAULT_PENETRATION: f64 = 0.75;
+
+/// One or more decks shuffled together. Cards are dealt from the front, and a cut card
+/// placed at the configured penetration marks when the shoe should be reshuffled.
+#[derive(Debug, Clone)]
+pub struct Shoe {
+    /// One deck of the game, copied `decks` times on every shuffle.
+    deck: Vec<Card>,
+    cards: Vec<Card>,
+    position: usize,
+    decks: usize,
+    penetration: f64,
+    cut_card: usize,
+}
+
+impl Shoe {
+    /// Creates a freshly shuffled shoe of `decks` standard decks (1 to `MAX_DECKS`), with the
+    /// cut card placed after `penetration` (a fraction between 0 and 1) of the cards.
+    pub fn new(decks: usize, penetration: f64) -> Self {
+        Shoe::with_deck(create_deck(), decks, penetration)
+    }
+
+    /// Like `new`, but built from copies of `deck` rather than the standard 52 cards, for
+    /// games dealt from a stripped deck.
+    pub fn with_deck(deck: Vec<Card>, decks: usize, penetration: f64) -> Self {
+        assert!(
+            (1..=MAX_DECKS).contains(&decks),
+            "a shoe holds between 1 and {} decks, got {}",
+            MAX_DECKS,
+            decks
+        );
+        assert!(
+            penetration > 0.0 && penetration <= 1.0,
+            "penetration must be between 0 and 1, got {}",
+            penetration
+        );
+        let mut shoe = Shoe {
+            deck,
+            cards: Vec::new(),
+            position: 0,
+            decks,
+            penetration,
+            cut_card: 0,
+        };
+        shoe.reshuffle();
+        shoe
+    }
+
+    /// Creates a shoe that deals `cards` in exactly the given order, e.g. from a deck file
+    /// or in tests. The cut card sits behind the last card, so it is never reached early.
+    /// Should the cards run out mid-round, the shoe carries on as a fresh shuffle of
+    /// `fallback`'s decks.
+    pub fn from_cards(cards: Vec<Card>, fallback: Shoe) -> Self {
+        Shoe {
+            cut_card: cards.len(),
+            cards,
+            position: 0,
+            ..fallback
+        }
+    }
+
+    /// Gathers every card back into the shoe, shuffles it and reinserts the cut card.
+    pub fn reshuffle(&mut self) {
+        self.cards = self.deck.repeat(self.decks);
+        self.cards.shuffle(&mut thread_rng());
+        self.position = 0;
+        self.cut_card = (self.cards.len() as f64 * self.penetration).round() as usize;
+    }
+
+    /// Deals the next card, reshuffling first if the shoe has run dry.
+    pub fn draw(&mut self) -> Card {
+        if self.remaining() == 0 {
+            self.reshuffle();
+        }
+        let card = self.cards[self.position];
+        self.position += 1;
+        card
+    }
+
+    /// True once the cut card has come out. The current round is finished as normal and
+    /// the shoe should be reshuffled before the next one.
+    pub fn cut_card_reached(&self) -> bool {
+        self.position >= self.cut_card
+    }
+
+    /// The cards dealt since the last shuffle, in dealing order.
+    pub fn dealt_cards(&self) -> &[Card] {
+        &self.cards[..self.position]
+    }
+
+    /// The cards still waiting to be dealt, in dealing order.
+    pub fn undealt(&self) -> &[Card] {
+        &self.cards[self.position..]
+    }
+
+    /// Removes and returns the undealt card at `index` (relative to `undealt()`).
+    pub fn take(&mut self, index: usize) -> Option<Card> {
+        if index < self.remaining() {
+            if self.position + index < self.cut_card {
+                self.cut_card -= 1;
+            }
+            Some(self.cards.remove(self.position + index))
+        } else {
+            None
+        }
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.cards.len() - self.position
+    }
+
+    /// How many cards can still be dealt before the cut card comes out.
+    pub fn cards_before_cut(&self) -> usize {
+        self.cut_card.saturating_sub(self.position)
+    }
+
+    pub fn dealt(&self) -> usize {
+        self.position
+    }
+
+    pub fn decks(&self) -> usize {
+        self.decks
+    }
+}
+
+/// Colours a card red or black according to its suit.
+pub fn format_card(card: &Card) -> ColoredString {
+    let colored_card = card.to_string().bold();
+    if card.suit.is_red() {
+        colored_card.red()
+    } else {
+        colored_card.black()
+    }
+}
+
+/// Formats a list of cards as a comma separated, coloured string.
+pub fn format_hand(hand: &[Card]) -> String {
+    hand.iter()
+        .map(|card| format_card(card).to_string())
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Reads cards written like "AS KD 10H", for tests.
+#[cfg(test)]
+pub(crate) fn cards(s: &str) -> Vec<Card> {
+    s.split_whitespace()
+        .map(|card| card.parse().unwrap())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_card_round_trips_through_string() {
+        for card in create_deck() {
+            assert_eq!(card.to_string().parse::<Card>(), Ok(card));
+        }
+        assert_eq!(
+            "queen of hearts".parse::<Card>(),
+            Ok(Card::new(Rank::Queen, Suit::Hearts))
+        );
+    }
+
+    #[test]
+    fn test_malformed_cards_are_rejected() {
+        assert!("Queen Hearts".parse::<Card>().is_err());
+        assert!("11 of Hearts".parse::<Card>().is_err());
+        assert!("Ace of Stars".parse::<Card>().is_err());
+        assert!("".parse::<Card>().is_err());
+        assert!("QX".parse::<Card>().is_err());
+        assert!("Q".parse::<Card>().is_err());
+    }
+
+    #[test]
+    fn test_short_card_names() {
+        assert_eq!("QH".parse(), Ok(Card::new(Rank::Queen, Suit::Hearts)));
+        assert_eq!("10d".parse(), Ok(Card::new(Rank::Ten, Suit::Diamonds)));
+        assert_eq!("As".parse(), Ok(Card::new(Rank::Ace, Suit::Spades)));
+    }
+
+    #[test]
+    fn test_parse_deck_files() {
+        let expected = vec![
+            Card::new(Rank::Ace, Suit::Spades),
+            Card::new(Rank::Eight, Suit::Hearts),
+            Card::new(Rank::King, Suit::Clubs),
+        ];
+        let text = "# split aces\nAce of Spades, 8H\n\nKC # dealer\n";
+        assert_eq!(parse_deck(text), Ok(expected.clone()));
+        let json = r#"[ "Ace of Spades", "8H",
+                        "KC" ]"#;
+        assert_eq!(parse_deck(json), Ok(expected));
+        assert_eq!(parse_deck("[]"), Ok(vec![]));
+
+        assert_eq!(
+            parse_deck("AS\nZZ").unwrap_err().0,
+            "line 2: unknown rank 'Z'"
+        );
+        assert!(parse_deck(r#"["AS" "KC"]"#).is_err());
+        assert!(parse_deck(r#"["AS", 7]"#).is_err());
+    }
+
+    #[test]
+    fn test_shoe_deals_every_card_once() {
+        let mut shoe = Shoe::new(2, 1.0);
+        assert_eq!(shoe.remaining(), 104);
+        let mut dealt: Vec<Card> = (0..104).map(|_| shoe.draw()).collect();
+        assert_eq!(shoe.remaining(), 0);
+        dealt.sort_by_key(|card| (card.suit, card.rank));
+        dealt.dedup();
+        assert_eq!(dealt.len(), 52);
+    }
+
+    #[test]
+    fn test_cut_card_penetration() {
+        let mut shoe = Shoe::new(6, 0.75);
+        assert_eq!(shoe.remaining(), 312);
+        assert_eq!(shoe.cards_before_cut(), 234);
+        for _ in 0..233 {
+            shoe.draw();
+        }
+        assert!(!shoe.cut_card_reached());
+        shoe.draw();
+        assert!(shoe.cut_card_reached());
+        shoe.reshuffle();
+        assert!(!shoe.cut_card_reached());
+        assert_eq!(shoe.remaining(), 312);
+    }
+
+    #[test]
+    fn test_shoe_from_cards_keeps_order() {
+        let ace = Card::new(Rank::Ace, Suit::Spades);
+        let king = Card::new(Rank::King, Suit::Clubs);
+        let mut shoe = Shoe::from_cards(vec![ace, king], Shoe::new(2, 0.5));
+        assert_eq!(shoe.take(1), Some(king));
+        assert_eq!(shoe.draw(), ace);
+        assert_eq!(shoe.remaining(), 0);
+
+        // Running out deals on from a shuffle of the fallback's decks, with its cut card
+        shoe.draw();
+        assert_eq!(shoe.remaining(), 103);
+        assert_eq!(shoe.cards_before_cut(), 51);
+    }
+}
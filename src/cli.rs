@@ -0,0 +1,49 @@
+//! Helpers shared by the command line games in `src/bin`.
+
+/// Removes `flag` and the value after it from `args`, if the flag is there.
+pub fn take_flag_value(args: &mut Vec<String>, flag: &str) -> Result<Option<String>, String> {
+    match args.iter().position(|arg| arg == flag) {
+        Some(index) if index + 1 < args.len() => {
+            args.remove(index);
+            Ok(Some(args.remove(index)))
+        }
+        Some(_) => Err(format!("missing value for {}", flag)),
+        None => Ok(None),
+    }
+}
+
+/// A change to the player's balance, e.g. "+$10", "-$5" or "$0".
+pub fn format_net(net: i32) -> String {
+    match net {
+        n if n > 0 => format!("+${}", n),
+        n if n < 0 => format!("-${}", -n),
+        _ => "$0".to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_take_flag_value() {
+        let mut args: Vec<String> = ["--seats", "3", "--quiz"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        assert_eq!(
+            take_flag_value(&mut args, "--seats"),
+            Ok(Some("3".to_string()))
+        );
+        assert_eq!(args, ["--quiz"]);
+        assert_eq!(take_flag_value(&mut args, "--bots"), Ok(None));
+        assert!(take_flag_value(&mut args, "--quiz").is_err());
+    }
+
+    #[test]
+    fn test_format_net() {
+        assert_eq!(format_net(10), "+$10");
+        assert_eq!(format_net(-5), "-$5");
+        assert_eq!(format_net(0), "$0");
+    }
+}
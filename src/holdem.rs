@@ -0,0 +1,678 @@
+//! No-limit Texas Hold'em: blinds, the betting rounds, side pots, the showdown and bot
+//! opponents.
+
+use crate::cards::{create_deck, Card, Shoe};
+use crate::poker::{best_hand, HandValue};
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+
+/// Seats at the table, the player's own included.
+pub const MAX_PLAYERS: usize = 6;
+
+/// How many random finishes a bot deals out to judge its hand.
+const EQUITY_TRIALS: u32 = 300;
+
+/// The four betting rounds of a hand, named after what is on the board.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+impl Street {
+    /// How many board cards are out during this street's betting.
+    pub fn board_cards(self) -> usize {
+        match self {
+            Street::Preflop => 0,
+            Street::Flop => 3,
+            Street::Turn => 4,
+            Street::River => 5,
+        }
+    }
+
+    fn next(self) -> Street {
+        match self {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            Street::Turn | Street::River => Street::River,
+        }
+    }
+}
+
+impl fmt::Display for Street {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Street::Preflop => "Pre-flop",
+            Street::Flop => "Flop",
+            Street::Turn => "Turn",
+            Street::River => "River",
+        })
+    }
+}
+
+/// What a player does when it is their turn.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    Fold,
+    Check,
+    /// Matches the current bet, or goes all in for less when that is all the player has.
+    Call,
+    /// Bets or raises so the player's bet on this street comes to this total.
+    RaiseTo(u32),
+    AllIn,
+}
+
+/// A bot's playing style. Aggression runs from 0, which calls along and seldom raises or
+/// bluffs, to 1, which raises anything decent, bets big and bluffs often.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bot {
+    pub aggression: f64,
+}
+
+impl Bot {
+    /// Picks an action for the bot in `seat`. It deals out random finishes against the
+    /// players still in to judge its chances, raises when they are well above an even share
+    /// of the pot (or when bluffing), and otherwise calls when the pot odds are good enough.
+    pub fn decide(&self, table: &Table, seat: usize, rng: &mut impl Rng) -> Action {
+        let player = &table.players[seat];
+        let opponents = table.players_in_hand() - 1;
+        let equity = equity(&player.hole, &table.board, opponents, EQUITY_TRIALS, rng);
+        let strength = equity * (opponents + 1) as f64;
+        let to_call = table.to_call(seat);
+        let pot = table.pot_total();
+
+        let bluffing = rng.gen_bool(0.2 * self.aggression.clamp(0.0, 1.0));
+        if (strength >= 1.8 - 0.6 * self.aggression || bluffing) && table.can_raise(seat) {
+            let size = ((pot + to_call) as f64 * (0.5 + 0.5 * self.aggression)).round() as u32;
+            let total = (table.current_bet() + size).max(table.min_raise_to());
+            return if total >= player.bet + player.stack {
+                Action::AllIn
+            } else {
+                Action::RaiseTo(total)
+            };
+        }
+        if to_call == 0 {
+            return Action::Check;
+        }
+        let pot_odds = to_call as f64 / (pot + to_call) as f64;
+        if equity >= pot_odds * (1.3 - 0.5 * self.aggression) {
+            Action::Call
+        } else {
+            Action::Fold
+        }
+    }
+}
+
+/// The share of the pot `hole` wins on average against `opponents` random hands, from
+/// `trials` random finishes of the board. A tie counts as a split.
+pub fn equity(
+    hole: &[Card],
+    board: &[Card],
+    opponents: usize,
+    trials: u32,
+    rng: &mut impl Rng,
+) -> f64 {
+    let mut deck: Vec<Card> = create_deck()
+        .into_iter()
+        .filter(|card| !hole.contains(card) && !board.contains(card))
+        .collect();
+    let missing = 5 - board.len();
+    let mut won = 0.0;
+    for _ in 0..trials {
+        let (dealt, _) = deck.partial_shuffle(rng, missing + 2 * opponents);
+        let (rest_of_board, holes) = dealt.split_at(missing);
+        let full_board = [board, rest_of_board].concat();
+        let mine = best_hand(&[hole, &full_board].concat());
+        let theirs: Vec<HandValue> = holes
+            .chunks(2)
+            .map(|cards| best_hand(&[cards, &full_board].concat()))
+            .collect();
+        if theirs.iter().all(|&value| value <= mine) {
+            let ties = theirs.iter().filter(|&&value| value == mine).count();
+            won += 1.0 / (ties + 1) as f64;
+        }
+    }
+    won / trials as f64
+}
+
+/// Someone at the table, with their chips and their part in the current hand.
+#[derive(Debug, Clone)]
+pub struct Player {
+    pub name: String,
+    pub stack: u32,
+    /// Empty when the player had no chips to play the hand.
+    pub hole: Vec<Card>,
+    /// Chips put in on the current street.
+    pub bet: u32,
+    /// Chips put in over the whole hand, which decides the side pots.
+    pub committed: u32,
+    pub folded: bool,
+    /// `None` for the person at the keyboard.
+    pub bot: Option<Bot>,
+    /// Whether the player has acted since the last full raise.
+    acted: bool,
+}
+
+impl Player {
+    pub fn new(name: &str, stack: u32, bot: Option<Bot>) -> Self {
+        Player {
+            name: name.to_string(),
+            stack,
+            hole: Vec::new(),
+            bet: 0,
+            committed: 0,
+            folded: false,
+            bot,
+            acted: false,
+        }
+    }
+
+    /// Whether the player still has a claim on the pot.
+    pub fn in_hand(&self) -> bool {
+        !self.hole.is_empty() && !self.folded
+    }
+
+    pub fn is_all_in(&self) -> bool {
+        self.in_hand() && self.stack == 0
+    }
+
+    fn can_act(&self) -> bool {
+        self.in_hand() && self.stack > 0
+    }
+
+    /// Moves up to `amount` from the stack into the pot.
+    fn put_in(&mut self, amount: u32) {
+        let amount = amount.min(self.stack);
+        self.stack -= amount;
+        self.bet += amount;
+        self.committed += amount;
+    }
+}
+
+/// A pot and the seats that can win it. The main pot comes first; each side pot after it
+/// leaves out the players who were all in for less.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pot {
+    pub amount: u32,
+    pub eligible: Vec<usize>,
+}
+
+/// How a pot was shared out at the end of a hand.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PotResult {
+    pub amount: u32,
+    pub winners: Vec<usize>,
+    /// The winning hand, or `None` when only one player had a claim on the pot.
+    pub value: Option<HandValue>,
+}
+
+/// A no-limit table and the hand being played at it.
+pub struct Table {
+    pub players: Vec<Player>,
+    pub button: usize,
+    pub small_blind: u32,
+    pub big_blind: u32,
+    pub board: Vec<Card>,
+    pub street: Street,
+    shoe: Shoe,
+    turn: Option<usize>,
+    current_bet: u32,
+    /// The size of the last full bet or raise on this street, which the next raise must
+    /// at least match.
+    last_raise: u32,
+}
+
+impl Table {
+    /// Seats `players` in order. The button starts on the first seat.
+    pub fn new(players: Vec<Player>, small_blind: u32, big_blind: u32) -> Self {
+        assert!(
+            (2..=MAX_PLAYERS).contains(&players.len()),
+            "a table seats 2 to {} players",
+            MAX_PLAYERS
+        );
+        Table {
+            button: players.len() - 1,
+            players,
+            small_blind,
+            big_blind,
+            board: Vec::new(),
+            street: Street::Preflop,
+            shoe: Shoe::new(1, 1.0),
+            turn: None,
+            current_bet: 0,
+            last_raise: big_blind,
+        }
+    }
+
+    /// How many players have chips to play the next hand.
+    pub fn players_with_chips(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|player| player.stack > 0)
+            .count()
+    }
+
+    /// How many players still have a claim on the pot.
+    pub fn players_in_hand(&self) -> usize {
+        self.players
+            .iter()
+            .filter(|player| player.in_hand())
+            .count()
+    }
+
+    /// Moves the button on, shuffles, deals two cards to everyone with chips and posts the
+    /// blinds. Heads-up the button posts the small blind and acts first before the flop.
+    pub fn start_hand(&mut self) {
+        assert!(self.players_with_chips() >= 2, "a hand needs two players");
+        for player in &mut self.players {
+            player.hole.clear();
+            player.bet = 0;
+            player.committed = 0;
+            player.folded = false;
+            player.acted = false;
+        }
+        self.board.clear();
+        self.street = Street::Preflop;
+        self.shoe.reshuffle();
+        self.button = self.next_with_chips(self.button);
+
+        for _ in 0..2 {
+            let mut seat = self.button;
+            for _ in 0..self.players_with_chips() {
+                seat = self.next_with_chips(seat);
+                let card = self.shoe.draw();
+                self.players[seat].hole.push(card);
+            }
+        }
+        let small = match self.players_with_chips() {
+            2 => self.button,
+            _ => self.next_with_chips(self.button),
+        };
+        let big = self.next_with_chips(small);
+        self.players[small].put_in(self.small_blind);
+        self.players[big].put_in(self.big_blind);
+        self.current_bet = self.big_blind;
+        self.last_raise = self.big_blind;
+        self.turn = self.next_to_act(big);
+    }
+
+    /// The seat due to act, or `None` once the street's betting is over.
+    pub fn to_act(&self) -> Option<usize> {
+        self.turn
+    }
+
+    /// The biggest bet on this street, which everyone still in has to match.
+    pub fn current_bet(&self) -> u32 {
+        self.current_bet
+    }
+
+    /// What `seat` has to put in to call, limited to what they have left.
+    pub fn to_call(&self, seat: usize) -> u32 {
+        let player = &self.players[seat];
+        self.current_bet
+            .saturating_sub(player.bet)
+            .min(player.stack)
+    }
+
+    /// The smallest total a bet or raise can come to, short of going all in.
+    pub fn min_raise_to(&self) -> u32 {
+        self.current_bet + self.last_raise
+    }
+
+    /// Whether `seat` may bet or raise. A player who has already acted can only call or
+    /// fold when a short all in raises them, since that does not reopen the betting.
+    pub fn can_raise(&self, seat: usize) -> bool {
+        let player = &self.players[seat];
+        !player.acted && player.stack > self.to_call(seat)
+    }
+
+    /// Every chip put in this hand, bets on the current street included.
+    pub fn pot_total(&self) -> u32 {
+        self.players.iter().map(|player| player.committed).sum()
+    }
+
+    /// Plays `action` for the seat due to act. A raise must be at least the size of the
+    /// last bet or raise on the street unless it puts the player all in. A full raise lets
+    /// everyone act again, while a short all in only asks those who have acted to call
+    /// the difference or fold.
+    pub fn act(&mut self, action: Action) -> Result<(), String> {
+        let seat = self.turn.ok_or("No one is due to act")?;
+        let to_call = self.to_call(seat);
+        let player = &self.players[seat];
+        let all_in_total = player.bet + player.stack;
+        let can_raise = self.can_raise(seat);
+        let action = match action {
+            Action::AllIn if can_raise => Action::RaiseTo(all_in_total),
+            Action::AllIn => Action::Call,
+            action => action,
+        };
+        match action {
+            Action::Fold => self.players[seat].folded = true,
+            Action::Check if to_call > 0 => {
+                return Err(format!("You can't check, it's ${} to call", to_call));
+            }
+            Action::Check => {}
+            Action::Call => self.players[seat].put_in(to_call),
+            Action::RaiseTo(_) if !can_raise => {
+                return Err(format!("You can't raise, only call ${} or fold", to_call));
+            }
+            Action::RaiseTo(total) => {
+                if total <= self.current_bet {
+                    return Err(format!(
+                        "A raise has to go above the current bet of ${}",
+                        self.current_bet
+                    ));
+                }
+                if total > all_in_total {
+                    return Err(format!("You can only raise to ${}", all_in_total));
+                }
+                if total < self.min_raise_to() && total < all_in_total {
+                    return Err(format!("The minimum raise is to ${}", self.min_raise_to()));
+                }
+                let player = &mut self.players[seat];
+                player.put_in(total - player.bet);
+                let raise = total - self.current_bet;
+                self.current_bet = total;
+                if raise >= self.last_raise {
+                    self.last_raise = raise;
+                    for player in &mut self.players {
+                        player.acted = false;
+                    }
+                }
+            }
+            Action::AllIn => unreachable!("all in is played as a call or a raise"),
+        }
+        self.players[seat].acted = true;
+        self.turn = self.next_to_act(seat);
+        Ok(())
+    }
+
+    /// Whether the hand is over: everyone else has folded, or the river's betting is done.
+    /// When everyone left is all in, the board is still dealt out street by street.
+    pub fn hand_over(&self) -> bool {
+        self.players_in_hand() < 2 || (self.turn.is_none() && self.street == Street::River)
+    }
+
+    /// Ends the street's betting, burns a card and deals the next board cards.
+    pub fn next_street(&mut self) {
+        assert!(self.street != Street::River, "the river is the last street");
+        self.street = self.street.next();
+        self.shoe.draw();
+        while self.board.len() < self.street.board_cards() {
+            let card = self.shoe.draw();
+            self.board.push(card);
+        }
+        for player in &mut self.players {
+            player.bet = 0;
+            player.acted = false;
+        }
+        self.current_bet = 0;
+        self.last_raise = self.big_blind;
+        self.turn = self.next_to_act(self.button);
+    }
+
+    /// The main pot and any side pots, from what everyone has put in this hand. Chips that
+    /// folded players put in beyond the biggest live stake go to the last pot.
+    pub fn pots(&self) -> Vec<Pot> {
+        let mut levels: Vec<u32> = self
+            .players
+            .iter()
+            .filter(|player| player.in_hand() && player.committed > 0)
+            .map(|player| player.committed)
+            .collect();
+        levels.sort_unstable();
+        levels.dedup();
+
+        let mut pots: Vec<Pot> = Vec::new();
+        let mut previous = 0;
+        for level in levels {
+            let amount = self
+                .players
+                .iter()
+                .map(|player| player.committed.min(level) - player.committed.min(previous))
+                .sum();
+            let eligible = (0..self.players.len())
+                .filter(|&seat| {
+                    let player = &self.players[seat];
+                    player.in_hand() && player.committed >= level
+                })
+                .collect();
+            pots.push(Pot { amount, eligible });
+            previous = level;
+        }
+        if let Some(last) = pots.last_mut() {
+            last.amount += self
+                .players
+                .iter()
+                .map(|player| player.committed.saturating_sub(previous))
+                .sum::<u32>();
+        }
+        pots
+    }
+
+    /// Shows down the hands still in and pays out every pot to the best hand among the
+    /// players who can win it. Split pots share out their odd chips from the left of the
+    /// button.
+    pub fn settle(&mut self) -> Vec<PotResult> {
+        let values: Vec<Option<HandValue>> = self
+            .players
+            .iter()
+            .map(|player| {
+                (player.in_hand() && self.players_in_hand() > 1)
+                    .then(|| best_hand(&[&player.hole[..], &self.board].concat()))
+            })
+            .collect();
+        let seats = self.players.len();
+        let mut results = Vec::new();
+        for pot in self.pots() {
+            let best = pot.eligible.iter().filter_map(|&seat| values[seat]).max();
+            let mut winners: Vec<usize> = pot
+                .eligible
+                .iter()
+                .copied()
+                .filter(|&seat| pot.eligible.len() == 1 || values[seat] == best)
+                .collect();
+            winners.sort_by_key(|&seat| (seat + seats - self.button - 1) % seats);
+            let share = pot.amount / winners.len() as u32;
+            let odd_chips = pot.amount as usize % winners.len();
+            for (index, &seat) in winners.iter().enumerate() {
+                self.players[seat].stack += share + u32::from(index < odd_chips);
+            }
+            results.push(PotResult {
+                amount: pot.amount,
+                winners,
+                value: best.filter(|_| pot.eligible.len() > 1),
+            });
+        }
+        self.turn = None;
+        results
+    }
+
+    /// The next seat after `seat` that has chips.
+    fn next_with_chips(&self, seat: usize) -> usize {
+        let seats = self.players.len();
+        (1..=seats)
+            .map(|step| (seat + step) % seats)
+            .find(|&next| self.players[next].stack > 0)
+            .unwrap_or(seat)
+    }
+
+    /// The next seat after `seat` that still has to act on this street: anyone with chips
+    /// facing a bet, or who has not acted yet while someone else could still respond.
+    fn next_to_act(&self, seat: usize) -> Option<usize> {
+        if self.players_in_hand() < 2 {
+            return None;
+        }
+        let can_act = self
+            .players
+            .iter()
+            .filter(|player| player.can_act())
+            .count();
+        let seats = self.players.len();
+        (1..=seats).map(|step| (seat + step) % seats).find(|&next| {
+            let player = &self.players[next];
+            player.can_act() && (player.bet < self.current_bet || (!player.acted && can_act > 1))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cards;
+    use crate::poker::Category;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn table(stacks: &[u32]) -> Table {
+        let players = stacks
+            .iter()
+            .enumerate()
+            .map(|(seat, &stack)| Player::new(&format!("P{}", seat), stack, None))
+            .collect();
+        Table::new(players, 1, 2)
+    }
+
+    #[test]
+    fn test_blinds_and_big_blind_option() {
+        let mut table = table(&[100, 100, 100]);
+        table.start_hand();
+        assert_eq!(table.button, 0);
+        assert_eq!((table.players[1].bet, table.players[2].bet), (1, 2));
+        assert!(table.players.iter().all(|player| player.hole.len() == 2));
+        assert_eq!(table.to_act(), Some(0));
+        table.act(Action::Call).unwrap();
+        table.act(Action::Call).unwrap();
+        // The big blind has not acted yet, so gets the option to raise
+        assert_eq!(table.to_act(), Some(2));
+        table.act(Action::Check).unwrap();
+        assert_eq!(table.to_act(), None);
+        assert_eq!(table.pot_total(), 6);
+
+        table.next_street();
+        assert_eq!(table.board.len(), 3);
+        assert_eq!(table.to_act(), Some(1));
+
+        // Heads-up the button posts the small blind and acts first
+        let mut table = self::table(&[100, 0, 100]);
+        table.start_hand();
+        assert!(table.players[1].hole.is_empty());
+        assert_eq!((table.players[0].bet, table.players[2].bet), (1, 2));
+        assert_eq!(table.to_act(), Some(0));
+    }
+
+    #[test]
+    fn test_raises() {
+        let mut table = table(&[100, 100, 16]);
+        table.start_hand();
+        assert!(table.act(Action::Check).is_err());
+        assert!(table.act(Action::RaiseTo(3)).is_err());
+        assert!(table.act(Action::RaiseTo(101)).is_err());
+        table.act(Action::RaiseTo(10)).unwrap();
+        assert_eq!(table.min_raise_to(), 18);
+        table.act(Action::Fold).unwrap();
+        // A short all in is allowed, but only asks the raiser to call the difference
+        table.act(Action::AllIn).unwrap();
+        assert_eq!(table.players[2].stack, 0);
+        assert_eq!(table.to_act(), Some(0));
+        assert!(!table.can_raise(0));
+        assert!(table.act(Action::RaiseTo(30)).is_err());
+        table.act(Action::AllIn).unwrap();
+        assert_eq!(table.players[0].bet, 16);
+        assert_eq!(table.to_act(), None);
+        assert!(!table.hand_over());
+        table.next_street();
+        // Only one player can still bet, so there is nothing to do but deal
+        assert_eq!(table.to_act(), None);
+    }
+
+    #[test]
+    fn test_side_pots() {
+        let mut table = table(&[0, 0, 0, 0]);
+        for (player, (committed, folded)) in
+            table
+                .players
+                .iter_mut()
+                .zip([(50, false), (100, false), (80, true), (200, false)])
+        {
+            player.hole = cards("2C 3D");
+            player.committed = committed;
+            player.folded = folded;
+        }
+        assert_eq!(
+            table.pots(),
+            vec![
+                Pot {
+                    amount: 200,
+                    eligible: vec![0, 1, 3]
+                },
+                Pot {
+                    amount: 130,
+                    eligible: vec![1, 3]
+                },
+                Pot {
+                    amount: 100,
+                    eligible: vec![3]
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_showdown() {
+        let mut table = table(&[0, 0, 0]);
+        table.button = 0;
+        table.board = cards("2C 7D 9H JS KD");
+        for (player, (hole, committed)) in
+            table
+                .players
+                .iter_mut()
+                .zip([("AH AD", 11), ("AC AS", 25), ("KH KC", 25)])
+        {
+            player.hole = cards(hole);
+            player.committed = committed;
+        }
+        let results = table.settle();
+        // Player 2's set of kings wins everything; no one else can win the side pot
+        assert_eq!(results[0].winners, vec![2]);
+        assert_eq!(results[0].value.unwrap().category, Category::ThreeOfAKind);
+        assert_eq!(results[1].winners, vec![2]);
+        assert_eq!(table.players[2].stack, 61);
+
+        // Two pairs of aces split the pot, the odd chip going left of the button
+        table.players[2].folded = true;
+        for player in &mut table.players {
+            player.stack = 0;
+        }
+        table.players[0].committed = 25;
+        let results = table.settle();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].winners, vec![1, 0]);
+        assert_eq!((table.players[0].stack, table.players[1].stack), (37, 38));
+    }
+
+    #[test]
+    fn test_bots() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let aces = equity(&cards("AH AD"), &[], 1, 2000, &mut rng);
+        assert!((aces - 0.85).abs() < 0.03, "{}", aces);
+
+        let mut table = table(&[200, 200]);
+        table.start_hand();
+        table.players[0].hole = cards("7C 2D");
+        table.players[1].hole = cards("AH AD");
+        table.act(Action::AllIn).unwrap();
+        let passive = Bot { aggression: 0.0 };
+        assert_ne!(passive.decide(&table, 1, &mut rng), Action::Fold);
+
+        let mut table = self::table(&[200, 200]);
+        table.start_hand();
+        table.players[0].hole = cards("AH AD");
+        table.players[1].hole = cards("7C 2D");
+        table.act(Action::AllIn).unwrap();
+        assert_eq!(passive.decide(&table, 1, &mut rng), Action::Fold);
+    }
+}
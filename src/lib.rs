@@ -0,0 +1,11 @@
+//! Shared game logic used by the casino binaries in `src/bin`.
+
+pub mod baccarat;
+pub mod blackjack;
+pub mod cards;
+pub mod cli;
+pub mod holdem;
+pub mod poker;
+pub mod roulette;
+pub mod three_card_poker;
+pub mod video_poker;
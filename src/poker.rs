@@ -0,0 +1,351 @@
+//! Poker hand rankings, shared by the poker games.
+
+use crate::cards::{Card, Rank};
+use std::fmt;
+
+/// The kinds of poker hand, from worst to best. A royal flush is kept apart from other
+/// straight flushes because paytables treat it specially, and five of a kind only
+/// happens with wild cards.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    ThreeOfAKind,
+    Straight,
+    Flush,
+    FullHouse,
+    FourOfAKind,
+    StraightFlush,
+    RoyalFlush,
+    FiveOfAKind,
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Category::HighCard => "High card",
+            Category::Pair => "Pair",
+            Category::TwoPair => "Two pair",
+            Category::ThreeOfAKind => "Three of a kind",
+            Category::Straight => "Straight",
+            Category::Flush => "Flush",
+            Category::FullHouse => "Full house",
+            Category::FourOfAKind => "Four of a kind",
+            Category::StraightFlush => "Straight flush",
+            Category::RoyalFlush => "Royal flush",
+            Category::FiveOfAKind => "Five of a kind",
+        })
+    }
+}
+
+/// A five card hand's standing: its category, then the ranks that break ties within it
+/// in the order they count. Grouped cards come first, biggest group first (the trips of
+/// a full house, then the pair), then kickers from high to low. Straights only list their
+/// top card. Unused places are padded with Twos, so values compare with `Ord`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HandValue {
+    pub category: Category,
+    pub ranks: [Rank; 5],
+}
+
+/// Ranks a hand of exactly five cards, without wild cards.
+pub fn evaluate(cards: &[Card]) -> HandValue {
+    assert_eq!(cards.len(), 5, "a poker hand has five cards");
+    let counts = rank_counts(cards.iter());
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+    let mut ranks = [Rank::Two; 5];
+    if let Some(high) = straight_high(&counts) {
+        ranks[0] = high;
+        let category = match (flush, high) {
+            (true, Rank::Ace) => Category::RoyalFlush,
+            (true, _) => Category::StraightFlush,
+            (false, _) => Category::Straight,
+        };
+        return HandValue { category, ranks };
+    }
+
+    let mut filled = 0;
+    for group in (1..=4).rev() {
+        for (index, &count) in counts.iter().enumerate().rev() {
+            if count == group {
+                ranks[filled] = Rank::ALL[index];
+                filled += 1;
+            }
+        }
+    }
+    let largest = counts.iter().max().copied().unwrap_or(0);
+    let pairs = counts.iter().filter(|&&count| count == 2).count();
+    let category = match (largest, pairs) {
+        (4, _) => Category::FourOfAKind,
+        (3, 1) => Category::FullHouse,
+        _ if flush => Category::Flush,
+        (3, _) => Category::ThreeOfAKind,
+        (2, 2) => Category::TwoPair,
+        (2, _) => Category::Pair,
+        _ => Category::HighCard,
+    };
+    HandValue { category, ranks }
+}
+
+/// The best five card hand out of five to seven cards, as in Hold'em where two hole cards
+/// and five on the board make seven.
+pub fn best_hand(cards: &[Card]) -> HandValue {
+    assert!((5..=7).contains(&cards.len()), "need five to seven cards");
+    let mut hand = [cards[0]; 5];
+    (0u8..1 << cards.len())
+        .filter(|mask| mask.count_ones() == 5)
+        .map(|mask| {
+            let picked = (0..cards.len()).filter(|index| mask & (1 << index) != 0);
+            for (slot, index) in picked.enumerate() {
+                hand[slot] = cards[index];
+            }
+            evaluate(&hand)
+        })
+        .max()
+        .unwrap()
+}
+
+/// The best category five cards can make when every card of the `wild` rank can stand in
+/// for any card. Without any wild cards this is `evaluate(cards).category`.
+pub fn wild_category(cards: &[Card], wild: Rank) -> Category {
+    assert_eq!(cards.len(), 5, "a poker hand has five cards");
+    let naturals: Vec<&Card> = cards.iter().filter(|card| card.rank != wild).collect();
+    let wilds = (cards.len() - naturals.len()) as u8;
+    let counts = rank_counts(naturals.iter().copied());
+    let largest = counts.iter().max().copied().unwrap_or(0);
+    let pairs = counts.iter().filter(|&&count| count == 2).count();
+    let flush = naturals
+        .iter()
+        .all(|card| card.suit == naturals.first().map_or(card.suit, |first| first.suit));
+    let straight = largest <= 1 && fits_straight(&counts);
+    let royal = straight && naturals.iter().all(|card| card.rank >= Rank::Ten);
+
+    if largest + wilds >= 5 {
+        Category::FiveOfAKind
+    } else if royal && flush {
+        Category::RoyalFlush
+    } else if straight && flush {
+        Category::StraightFlush
+    } else if largest + wilds >= 4 {
+        Category::FourOfAKind
+    } else if (largest == 3 && pairs == 1) || (wilds == 1 && pairs == 2) {
+        Category::FullHouse
+    } else if flush {
+        Category::Flush
+    } else if straight {
+        Category::Straight
+    } else if largest + wilds >= 3 {
+        Category::ThreeOfAKind
+    } else if pairs == 2 {
+        Category::TwoPair
+    } else if largest + wilds >= 2 {
+        Category::Pair
+    } else {
+        Category::HighCard
+    }
+}
+
+/// The kinds of three card hand, from worst to best. With only three cards a straight is
+/// harder to make than a flush, so it ranks above one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ThreeCardCategory {
+    HighCard,
+    Pair,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    StraightFlush,
+}
+
+impl fmt::Display for ThreeCardCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ThreeCardCategory::HighCard => "High card",
+            ThreeCardCategory::Pair => "Pair",
+            ThreeCardCategory::Flush => "Flush",
+            ThreeCardCategory::Straight => "Straight",
+            ThreeCardCategory::ThreeOfAKind => "Three of a kind",
+            ThreeCardCategory::StraightFlush => "Straight flush",
+        })
+    }
+}
+
+/// A three card hand's standing, ordered like `HandValue`: the pair before the kicker,
+/// otherwise ranks from high to low, and only the top card of a straight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ThreeCardValue {
+    pub category: ThreeCardCategory,
+    pub ranks: [Rank; 3],
+}
+
+/// Ranks a hand of exactly three cards. Ace-Two-Three is the lowest straight.
+pub fn evaluate_three_card(cards: &[Card]) -> ThreeCardValue {
+    assert_eq!(cards.len(), 3, "a three card poker hand has three cards");
+    let mut ranks = [cards[0].rank, cards[1].rank, cards[2].rank];
+    ranks.sort_unstable_by(|a, b| b.cmp(a));
+    let flush = cards.iter().all(|card| card.suit == cards[0].suit);
+    let distinct = ranks[0] != ranks[1] && ranks[1] != ranks[2];
+    let straight_high = match ranks {
+        [Rank::Ace, Rank::Three, Rank::Two] => Some(Rank::Three),
+        [high, _, low] if distinct && high as usize - low as usize == 2 => Some(high),
+        _ => None,
+    };
+
+    let (category, ranks) = match straight_high {
+        Some(high) if flush => (
+            ThreeCardCategory::StraightFlush,
+            [high, Rank::Two, Rank::Two],
+        ),
+        Some(high) => (ThreeCardCategory::Straight, [high, Rank::Two, Rank::Two]),
+        None if ranks[0] == ranks[2] => (ThreeCardCategory::ThreeOfAKind, ranks),
+        None if ranks[0] == ranks[1] => (ThreeCardCategory::Pair, ranks),
+        None if ranks[1] == ranks[2] => (ThreeCardCategory::Pair, [ranks[1], ranks[2], ranks[0]]),
+        None if flush => (ThreeCardCategory::Flush, ranks),
+        None => (ThreeCardCategory::HighCard, ranks),
+    };
+    ThreeCardValue { category, ranks }
+}
+
+/// How many cards of each rank there are, indexed Two (0) to Ace (12).
+fn rank_counts<'a>(cards: impl Iterator<Item = &'a Card>) -> [u8; 13] {
+    let mut counts = [0; 13];
+    for card in cards {
+        counts[card.rank as usize] += 1;
+    }
+    counts
+}
+
+/// The top card of the straight five distinct ranks make, if they do. An Ace plays high
+/// (Ten to Ace) or low (Ace to Five, a five high straight).
+fn straight_high(counts: &[u8; 13]) -> Option<Rank> {
+    if counts.iter().any(|&count| count > 1) || counts.iter().sum::<u8>() != 5 {
+        return None;
+    }
+    (4..13)
+        .rev()
+        .find(|&high| counts[high - 4..=high].iter().all(|&count| count == 1))
+        .map(|high| Rank::ALL[high])
+        .or_else(|| {
+            let wheel = counts[12] == 1 && counts[..4].iter().all(|&count| count == 1);
+            wheel.then_some(Rank::Five)
+        })
+}
+
+/// Whether every rank present fits inside one run of five, Ace high or low, so that wild
+/// cards could fill in the rest of a straight.
+fn fits_straight(counts: &[u8; 13]) -> bool {
+    let present = |index: usize| counts[index] > 0;
+    (4..13).any(|high| fits_window(counts, high - 4, high))
+        || (0..13).all(|index| !present(index) || index < 4 || index == 12)
+}
+
+/// Whether no rank outside `low..=high` is present.
+fn fits_window(counts: &[u8; 13], low: usize, high: usize) -> bool {
+    counts
+        .iter()
+        .enumerate()
+        .all(|(index, &count)| count == 0 || (low..=high).contains(&index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::{cards, create_deck};
+    use rand::rngs::StdRng;
+    use rand::seq::SliceRandom;
+    use rand::SeedableRng;
+
+    fn category(s: &str) -> Category {
+        evaluate(&cards(s)).category
+    }
+
+    #[test]
+    fn test_categories() {
+        assert_eq!(category("TH JH QH KH AH"), Category::RoyalFlush);
+        assert_eq!(category("9S TS JS QS KS"), Category::StraightFlush);
+        assert_eq!(category("AD 2D 3D 4D 5D"), Category::StraightFlush);
+        assert_eq!(category("7C 7D 7H 7S 2C"), Category::FourOfAKind);
+        assert_eq!(category("7C 7D 7H 2S 2C"), Category::FullHouse);
+        assert_eq!(category("2H 9H JH QH 4H"), Category::Flush);
+        assert_eq!(category("AC 2D 3H 4S 5C"), Category::Straight);
+        assert_eq!(category("TC JD QH KS AC"), Category::Straight);
+        assert_eq!(category("QC KD AH 2S 3C"), Category::HighCard);
+        assert_eq!(category("7C 7D 7H 2S 3C"), Category::ThreeOfAKind);
+        assert_eq!(category("7C 7D 3H 2S 3C"), Category::TwoPair);
+        assert_eq!(category("JC JD 3H 2S 4C"), Category::Pair);
+    }
+
+    #[test]
+    fn test_tie_breaks() {
+        let value = |s: &str| evaluate(&cards(s));
+        assert!(value("AC 2D 3H 4S 5C") < value("2C 3D 4H 5S 6C"));
+        assert!(value("KC KD 3H 2S 4C") < value("KC KD 3H 2S 5C"));
+        assert!(value("3C 3D 3H AS AC") < value("4C 4D 4H 2S 2C"));
+        assert!(value("9C 9D 5H 5S AC") < value("9C 9D 6H 6S 2C"));
+        assert_eq!(value("9C 9D 5H 5S AC"), value("9H 9S 5C 5D AD"));
+        assert_eq!(value("7C 7D 7H 2S 2C").ranks[..2], [Rank::Seven, Rank::Two]);
+    }
+
+    #[test]
+    fn test_best_hand() {
+        let best = |s: &str| best_hand(&cards(s));
+        assert_eq!(best("AH KH 2C 3D 4S 5H 9C").category, Category::Straight);
+        assert_eq!(best("AH KH QH JH 4S 5H 9C").category, Category::Flush);
+        assert_eq!(
+            best("AH KH QH JH 4S 5H 9C").ranks,
+            [Rank::Ace, Rank::King, Rank::Queen, Rank::Jack, Rank::Five]
+        );
+        assert_eq!(
+            best("7C 7D 7H 2S 2C 3C 3D").ranks[..2],
+            [Rank::Seven, Rank::Three]
+        );
+        assert_eq!(best("TH JH QH KH AH"), evaluate(&cards("TH JH QH KH AH")));
+    }
+
+    #[test]
+    fn test_three_card_hands() {
+        let three = |s: &str| evaluate_three_card(&cards(s));
+        assert_eq!(three("QH KH AH").category, ThreeCardCategory::StraightFlush);
+        assert_eq!(three("7C 7D 7H").category, ThreeCardCategory::ThreeOfAKind);
+        assert_eq!(three("AC 2D 3H").category, ThreeCardCategory::Straight);
+        assert_eq!(three("2H 9H JH").category, ThreeCardCategory::Flush);
+        assert_eq!(
+            three("KC 4D 4H").ranks,
+            [Rank::Four, Rank::Four, Rank::King]
+        );
+        assert_eq!(three("KC AD 2H").category, ThreeCardCategory::HighCard);
+        assert!(three("AC 2D 3H") < three("2C 3D 4H"));
+        assert!(three("2H 3H 5H") > three("AC KD JH").max(three("AC AD KH")));
+        assert!(three("QC 6D 4H") > three("QC 6D 3H"));
+    }
+
+    #[test]
+    fn test_wild_categories() {
+        let wild = |s: &str| wild_category(&cards(s), Rank::Two);
+        assert_eq!(wild("2C 2D 2H 2S 9C"), Category::FiveOfAKind);
+        assert_eq!(wild("2C TH JH QH AH"), Category::RoyalFlush);
+        assert_eq!(wild("2C 2D 5H 7H 8H"), Category::StraightFlush);
+        assert_eq!(wild("2C 2D 3H 5H AH"), Category::StraightFlush);
+        assert_eq!(wild("2C 9D 9H KS 4C"), Category::ThreeOfAKind);
+        assert_eq!(wild("2C 9D 9H KS KC"), Category::FullHouse);
+        assert_eq!(wild("2C 2D 9H KS 4C"), Category::ThreeOfAKind);
+        assert_eq!(wild("2C 6D 7H 8S TC"), Category::Straight);
+        assert_eq!(wild("2C 6D 7H 8S JC"), Category::Pair);
+        assert_eq!(wild("2C 6D 7D 8D JD"), Category::Flush);
+    }
+
+    #[test]
+    fn test_wild_category_matches_evaluate_without_wilds() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut deck = create_deck();
+        for _ in 0..2000 {
+            deck.shuffle(&mut rng);
+            let hand = &deck[..5];
+            if hand.iter().any(|card| card.rank == Rank::Two) {
+                continue;
+            }
+            assert_eq!(wild_category(hand, Rank::Two), evaluate(hand).category);
+        }
+    }
+}
@@ -0,0 +1,320 @@
+use super::{Outcome, Pocket, Wheel, ZeroRule, RED_NUMBERS};
+use std::fmt;
+
+/// A bet on the table layout. Inside bets cover one or a few neighbouring pockets, and
+/// outside bets cover a third or a half of the numbers, never the zeros. Numbers are laid
+/// out in twelve rows of three, 1-2-3 across the top, with the zeros above them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Bet {
+    Straight(Pocket),
+    /// Two pockets side by side or one above the other, lowest first.
+    Split(Pocket, Pocket),
+    /// A row of three, by its first number.
+    Street(u8),
+    /// A block of four, by its top left number.
+    Corner(u8),
+    /// Two rows of three, by the first number of the upper row.
+    SixLine(u8),
+    /// The zeros together with 1, 2 and 3: the first four on a single-zero wheel and the
+    /// top line on a double-zero one.
+    Basket,
+    Red,
+    Black,
+    Even,
+    Odd,
+    /// 1 to 18.
+    Low,
+    /// 19 to 36.
+    High,
+    /// 1 to 12, 13 to 24 or 25 to 36, numbered 1 to 3.
+    Dozen(u8),
+    /// The columns running down the layout, numbered 1 to 3 from the one starting at 1.
+    Column(u8),
+}
+
+/// The column (0 to 2) and row (0 to 11) of a number on the table layout.
+fn position(number: u8) -> (u8, u8) {
+    ((number - 1) % 3, (number - 1) / 3)
+}
+
+/// The numbers in the first row that each zero sits above on the layout, following the
+/// widths in the roulette binary's table: a lone 0 spans the whole row, 0 and 00 share
+/// the middle column, and with three zeros each has a column to itself.
+fn zero_neighbours(wheel: Wheel, zero: Pocket) -> &'static [u8] {
+    match (wheel, zero) {
+        (Wheel::European, Pocket::Zero) => &[1, 2, 3],
+        (Wheel::American, Pocket::Zero) => &[1, 2],
+        (Wheel::American, Pocket::DoubleZero) => &[2, 3],
+        (Wheel::TripleZero, Pocket::Zero) => &[1],
+        (Wheel::TripleZero, Pocket::DoubleZero) => &[2],
+        (Wheel::TripleZero, Pocket::TripleZero) => &[3],
+        _ => &[],
+    }
+}
+
+/// Whether `a` and `b` share an edge on the layout of `wheel`.
+fn adjacent(wheel: Wheel, a: Pocket, b: Pocket) -> bool {
+    match (a.number(), b.number()) {
+        (Some(a), Some(b)) => {
+            let ((column_a, row_a), (column_b, row_b)) = (position(a), position(b));
+            (row_a == row_b && column_a.abs_diff(column_b) == 1)
+                || (column_a == column_b && row_a.abs_diff(row_b) == 1)
+        }
+        (None, Some(number)) => zero_neighbours(wheel, a).contains(&number),
+        (Some(number), None) => zero_neighbours(wheel, b).contains(&number),
+        // The zeros sit side by side along the top
+        (None, None) => {
+            let zeros = wheel.zeros();
+            let index = |zero| zeros.iter().position(|&z| z == zero);
+            matches!((index(a), index(b)), (Some(i), Some(j)) if i.abs_diff(j) == 1)
+        }
+    }
+}
+
+impl Bet {
+    pub fn straight(wheel: Wheel, pocket: Pocket) -> Result<Bet, String> {
+        if !wheel.contains(pocket) {
+            return Err(format!("there is no {} on a {} wheel", pocket, wheel));
+        }
+        Ok(Bet::Straight(pocket))
+    }
+
+    pub fn split(wheel: Wheel, a: Pocket, b: Pocket) -> Result<Bet, String> {
+        for pocket in [a, b] {
+            Bet::straight(wheel, pocket)?;
+        }
+        if !adjacent(wheel, a, b) {
+            return Err(format!("{} and {} are not next to each other", a, b));
+        }
+        Ok(Bet::Split(a.min(b), a.max(b)))
+    }
+
+    /// The street starting at `first`, which must begin a row (1, 4, ... 34).
+    pub fn street(first: u8) -> Result<Bet, String> {
+        if first == 0 || first > 34 || position(first).0 != 0 {
+            return Err(format!("{} does not start a row of the table", first));
+        }
+        Ok(Bet::Street(first))
+    }
+
+    /// The corner with `a` and `b` at opposite corners, e.g. 17 and 21, in either order.
+    pub fn corner(a: u8, b: u8) -> Result<Bet, String> {
+        let (low, high) = (a.min(b), a.max(b));
+        if low == 0 || low > 32 || position(low).0 == 2 || high != low + 4 {
+            return Err(format!(
+                "{} and {} are not opposite corners of a block",
+                a, b
+            ));
+        }
+        Ok(Bet::Corner(low))
+    }
+
+    /// The six line starting at `first`, which must begin a row (1, 4, ... 31).
+    pub fn six_line(first: u8) -> Result<Bet, String> {
+        if first == 0 || first > 31 || position(first).0 != 0 {
+            return Err(format!("{} does not start a row with one below it", first));
+        }
+        Ok(Bet::SixLine(first))
+    }
+
+    /// Dozen `dozen`, from 1 to 3.
+    pub fn dozen(dozen: u8) -> Result<Bet, String> {
+        if !(1..=3).contains(&dozen) {
+            return Err(format!("dozen must be 1, 2 or 3, got {}", dozen));
+        }
+        Ok(Bet::Dozen(dozen))
+    }
+
+    /// Column `column`, from 1 to 3.
+    pub fn column(column: u8) -> Result<Bet, String> {
+        if !(1..=3).contains(&column) {
+            return Err(format!("column must be 1, 2 or 3, got {}", column));
+        }
+        Ok(Bet::Column(column))
+    }
+
+    /// Red/black, even/odd and 1-18/19-36, the bets the zero rules apply to.
+    pub fn is_even_money(self) -> bool {
+        matches!(
+            self,
+            Bet::Red | Bet::Black | Bet::Even | Bet::Odd | Bet::Low | Bet::High
+        )
+    }
+
+    /// The pockets the bet covers.
+    pub fn pockets(self, wheel: Wheel) -> Vec<Pocket> {
+        let numbers = |first: u8, count: u8| (first..first + count).map(Pocket::Number).collect();
+        let numbers_where = |covers: fn(u8) -> bool| {
+            (1..=36)
+                .filter(|&number| covers(number))
+                .map(Pocket::Number)
+                .collect()
+        };
+        match self {
+            Bet::Straight(pocket) => vec![pocket],
+            Bet::Split(a, b) => vec![a, b],
+            Bet::Street(first) => numbers(first, 3),
+            Bet::Corner(low) => [low, low + 1, low + 3, low + 4]
+                .into_iter()
+                .map(Pocket::Number)
+                .collect(),
+            Bet::SixLine(first) => numbers(first, 6),
+            Bet::Basket => {
+                let mut pockets = wheel.zeros().to_vec();
+                pockets.extend(numbers(1, 3));
+                pockets
+            }
+            Bet::Red => numbers_where(|n| RED_NUMBERS.contains(&n)),
+            Bet::Black => numbers_where(|n| !RED_NUMBERS.contains(&n)),
+            Bet::Even => numbers_where(|n| n % 2 == 0),
+            Bet::Odd => numbers_where(|n| n % 2 == 1),
+            Bet::Low => numbers(1, 18),
+            Bet::High => numbers(19, 18),
+            Bet::Dozen(dozen) => numbers(dozen * 12 - 11, 12),
+            Bet::Column(column) => numbers_where(match column {
+                1 => |n| n % 3 == 1,
+                2 => |n| n % 3 == 2,
+                _ => |n| n % 3 == 0,
+            }),
+        }
+    }
+
+    pub fn wins(self, wheel: Wheel, result: Pocket) -> bool {
+        self.pockets(wheel).contains(&result)
+    }
+
+    /// How the bet fares on a spin of `result`. Even-money bets go by `zero_rule` when the
+    /// ball lands on a zero, and `imprisoned` is whether the bet is riding in prison.
+    pub fn settle(
+        self,
+        wheel: Wheel,
+        zero_rule: ZeroRule,
+        result: Pocket,
+        imprisoned: bool,
+    ) -> Outcome {
+        let won = self.wins(wheel, result);
+        if self.is_even_money() {
+            zero_rule.settle_even_money(won, result, imprisoned)
+        } else if won {
+            Outcome::Win
+        } else {
+            Outcome::Lose
+        }
+    }
+
+    /// The winnings per unit staked: 36 divided by the number of pockets covered, less the
+    /// returned stake, rounded down. The double-zero top line pays 6:1.
+    pub fn payout(self, wheel: Wheel) -> i32 {
+        36 / self.pockets(wheel).len() as i32 - 1
+    }
+}
+
+impl fmt::Display for Bet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Bet::Straight(pocket) => write!(f, "straight up {}", pocket),
+            Bet::Split(a, b) => write!(f, "split {}-{}", a, b),
+            Bet::Street(first) => write!(f, "street {}-{}", first, first + 2),
+            Bet::Corner(low) => write!(f, "corner {}-{}", low, low + 4),
+            Bet::SixLine(first) => write!(f, "six line {}-{}", first, first + 5),
+            Bet::Basket => write!(f, "basket"),
+            Bet::Red => write!(f, "red"),
+            Bet::Black => write!(f, "black"),
+            Bet::Even => write!(f, "even"),
+            Bet::Odd => write!(f, "odd"),
+            Bet::Low => write!(f, "1-18"),
+            Bet::High => write!(f, "19-36"),
+            Bet::Dozen(dozen) => write!(f, "dozen {}", dozen),
+            Bet::Column(column) => write!(f, "column {}", column),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_splits_follow_the_layout() {
+        let n = Pocket::Number;
+        assert_eq!(
+            Bet::split(Wheel::American, n(11), n(8)),
+            Ok(Bet::Split(n(8), n(11)))
+        );
+        assert!(Bet::split(Wheel::American, n(2), n(3)).is_ok());
+        assert!(Bet::split(Wheel::American, n(3), n(4)).is_err());
+        assert!(Bet::split(Wheel::American, n(8), n(12)).is_err());
+        assert!(Bet::split(Wheel::European, Pocket::Zero, n(3)).is_ok());
+        assert!(Bet::split(Wheel::American, Pocket::Zero, n(3)).is_err());
+        assert!(Bet::split(Wheel::American, Pocket::DoubleZero, n(3)).is_ok());
+        assert!(Bet::split(Wheel::American, Pocket::Zero, Pocket::DoubleZero).is_ok());
+        assert!(Bet::split(Wheel::European, Pocket::Zero, Pocket::DoubleZero).is_err());
+        assert!(Bet::split(Wheel::TripleZero, Pocket::Zero, Pocket::TripleZero).is_err());
+    }
+
+    #[test]
+    fn test_inside_bets() {
+        let wheel = Wheel::American;
+        assert!(Bet::street(5).is_err());
+        assert!(Bet::street(37).is_err());
+        assert!(Bet::corner(17, 21).is_ok());
+        assert_eq!(Bet::corner(21, 17), Ok(Bet::Corner(17)));
+        assert!(Bet::corner(18, 22).is_err());
+        assert!(Bet::corner(33, 37).is_err());
+        assert!(Bet::six_line(34).is_err());
+        assert_eq!(
+            Bet::Corner(17).pockets(wheel),
+            [17, 18, 20, 21].map(Pocket::Number).to_vec()
+        );
+        assert!(Bet::SixLine(31).wins(wheel, Pocket::Number(36)));
+        assert!(!Bet::Street(7).wins(wheel, Pocket::Number(10)));
+        assert!(Bet::Basket.wins(wheel, Pocket::DoubleZero));
+
+        let payouts = [
+            Bet::Straight(Pocket::Zero),
+            Bet::Split(Pocket::Number(1), Pocket::Number(2)),
+            Bet::Street(1),
+            Bet::Corner(1),
+            Bet::SixLine(1),
+        ]
+        .map(|bet| bet.payout(wheel));
+        assert_eq!(payouts, [35, 17, 11, 8, 5]);
+        assert_eq!(Bet::Basket.payout(Wheel::European), 8);
+        assert_eq!(Bet::Basket.payout(Wheel::American), 6);
+        assert_eq!(Bet::Basket.payout(Wheel::TripleZero), 5);
+    }
+
+    #[test]
+    fn test_outside_bets() {
+        let wheel = Wheel::European;
+        for bet in [
+            Bet::Red,
+            Bet::Black,
+            Bet::Even,
+            Bet::Odd,
+            Bet::Low,
+            Bet::High,
+        ] {
+            assert_eq!(bet.pockets(wheel).len(), 18, "{}", bet);
+            assert_eq!(bet.payout(wheel), 1);
+            assert!(!bet.wins(wheel, Pocket::Zero));
+        }
+        for index in 1..=3 {
+            for bet in [Bet::Dozen(index), Bet::Column(index)] {
+                assert_eq!(bet.pockets(wheel).len(), 12, "{}", bet);
+                assert_eq!(bet.payout(wheel), 2);
+            }
+        }
+        assert!(Bet::Dozen(2).wins(wheel, Pocket::Number(13)));
+        assert!(Bet::Column(3).wins(wheel, Pocket::Number(36)));
+        assert!(Bet::dozen(4).is_err());
+        assert_eq!(
+            Bet::Red.settle(wheel, ZeroRule::LaPartage, Pocket::Zero, false),
+            Outcome::Partage
+        );
+        assert_eq!(
+            Bet::Dozen(1).settle(wheel, ZeroRule::LaPartage, Pocket::Zero, false),
+            Outcome::Lose
+        );
+    }
+}
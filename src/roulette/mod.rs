@@ -0,0 +1,19 @@
+//! Roulette, from the wheel to the bets placed on it:
+//!
+//! - [`Wheel`]: European, American and triple zero wheels and their pockets.
+//! - [`ZeroRule`]: La Partage and En Prison for even-money bets when a zero comes up.
+//! - [`Bet`]: the outside and inside bets, the pockets they cover and what they pay.
+//! - [`BetSlip`]: every wager on the table, settled against a single spin.
+//! - [`parse_bets`]: reads a line of bets such as `10 red; 5 n 17`.
+
+mod bets;
+mod parser;
+mod rules;
+mod slip;
+mod wheel;
+
+pub use bets::Bet;
+pub use parser::{parse_bet_argument, parse_bets, ParseError};
+pub use rules::{Outcome, ZeroRule};
+pub use slip::{net_change, BetSlip, Settlement, Wager};
+pub use wheel::{Colour, Pocket, Wheel, RED_NUMBERS};
@@ -0,0 +1,349 @@
+use super::{Bet, Pocket, Wheel};
+use std::fmt;
+
+/// A mistake in a line of bets, with the byte range of the token at fault.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub message: String,
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ParseError {
+    /// A row of carets under the token at fault, to print beneath `input`.
+    pub fn underline(&self, input: &str) -> String {
+        let indent = input[..self.start].chars().count();
+        let width = input[self.start..self.end].chars().count().max(1);
+        format!("{}{}", " ".repeat(indent), "^".repeat(width))
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+/// A word of the input and where it sits in the whole line.
+#[derive(Clone, Copy)]
+struct Token<'a> {
+    text: &'a str,
+    start: usize,
+}
+
+impl<'a> Token<'a> {
+    /// The token `text`, which must be a slice of `input`.
+    fn within(input: &str, text: &'a str) -> Self {
+        Token {
+            text,
+            start: text.as_ptr() as usize - input.as_ptr() as usize,
+        }
+    }
+
+    fn end(self) -> usize {
+        self.start + self.text.len()
+    }
+
+    fn error(self, message: String) -> ParseError {
+        ParseError {
+            message,
+            start: self.start,
+            end: self.end(),
+        }
+    }
+
+    /// The two halves of a token like "8-11".
+    fn pair(self) -> Result<(Token<'a>, Token<'a>), ParseError> {
+        let (a, b) = self.text.split_once('-').ok_or_else(|| {
+            self.error(format!(
+                "expected two numbers like 8-11, got '{}'",
+                self.text
+            ))
+        })?;
+        Ok((
+            Token {
+                text: a,
+                start: self.start,
+            },
+            Token {
+                text: b,
+                start: self.start + a.len() + 1,
+            },
+        ))
+    }
+
+    fn pocket(self, wheel: Wheel) -> Result<Pocket, ParseError> {
+        wheel.parse_pocket(self.text).map_err(|e| self.error(e))
+    }
+
+    /// One of the numbers 1 to 36.
+    fn number(self) -> Result<u8, ParseError> {
+        match self.text.parse() {
+            Ok(Pocket::Number(number)) => Ok(number),
+            _ => Err(self.error(format!(
+                "expected a number from 1 to 36, got '{}'",
+                self.text
+            ))),
+        }
+    }
+
+    /// A dozen or column, numbered 1 to 3.
+    fn one_to_three(self, what: &str) -> Result<u8, ParseError> {
+        match self.text.parse() {
+            Ok(n @ 1..=3) => Ok(n),
+            _ => Err(self.error(format!(
+                "expected a {} from 1 to 3, got '{}'",
+                what, self.text
+            ))),
+        }
+    }
+}
+
+/// Reads a line of bets such as `10 red; 5 split 8-11; 2 corner 17-21; 25 dozen 2; 1 n 00`:
+/// wagers separated by semicolons, each an amount followed by the bet.
+///
+/// The bets are `red`, `black`, `even`, `odd`, `low` (or `1-18`), `high` (or `19-36`),
+/// `dozen N`, `column N`, `n N`, `split A-B`, `street N` (or `N-M`), `corner A-B`,
+/// `line N` (or `N-M`) and `basket`, with the roulette menu's letters (`d`, `co`, `s`, `st`,
+/// `cr`, `sl`, `b`) accepted as well.
+pub fn parse_bets(wheel: Wheel, input: &str) -> Result<Vec<(Bet, i32)>, ParseError> {
+    let wagers = input
+        .split(';')
+        .filter(|segment| !segment.trim().is_empty())
+        .map(|segment| parse_wager(wheel, input, segment))
+        .collect::<Result<Vec<_>, _>>()?;
+    if wagers.is_empty() {
+        return Err(ParseError {
+            message: "expected at least one bet, e.g. '10 red'".to_string(),
+            start: 0,
+            end: input.len(),
+        });
+    }
+    Ok(wagers)
+}
+
+/// Reads one wager, `segment` being the part of `input` between semicolons.
+fn parse_wager(wheel: Wheel, input: &str, segment: &str) -> Result<(Bet, i32), ParseError> {
+    let mut tokens = segment
+        .split_whitespace()
+        .map(|text| Token::within(input, text));
+    let amount_token = tokens.next().expect("segment is not blank");
+    let amount = amount_token
+        .text
+        .parse()
+        .ok()
+        .filter(|&amount: &i32| amount > 0)
+        .ok_or_else(|| {
+            amount_token.error(format!(
+                "expected an amount to bet, got '{}'",
+                amount_token.text
+            ))
+        })?;
+    let kind = tokens.next().ok_or_else(|| ParseError {
+        message: format!("expected a bet after ${}, e.g. '{} red'", amount, amount),
+        start: amount_token.end(),
+        end: amount_token.end(),
+    })?;
+    let argument = tokens.next();
+
+    let bet = match kind.text.to_lowercase().as_str() {
+        "red" => Bet::Red,
+        "black" => Bet::Black,
+        "even" => Bet::Even,
+        "odd" => Bet::Odd,
+        "low" | "1-18" => Bet::Low,
+        "high" | "19-36" => Bet::High,
+        "basket" | "top-line" | "first-four" | "b" => Bet::Basket,
+        name => {
+            let hint = argument_hint(name)
+                .ok_or_else(|| kind.error(format!("unknown bet '{}'", kind.text)))?;
+            let token = argument.ok_or_else(|| ParseError {
+                message: format!("'{}' needs {}", kind.text, hint),
+                start: kind.end(),
+                end: kind.end(),
+            })?;
+            bet_with_argument(wheel, name, token)?
+        }
+    };
+
+    let needs_argument = !matches!(
+        bet,
+        Bet::Red | Bet::Black | Bet::Even | Bet::Odd | Bet::Low | Bet::High | Bet::Basket
+    );
+    let extra = if needs_argument {
+        tokens.next()
+    } else {
+        argument
+    };
+    if let Some(token) = extra {
+        return Err(token.error(format!("unexpected '{}' after {}", token.text, bet)));
+    }
+    Ok((bet, amount))
+}
+
+/// Reads what follows a bet of `kind` that takes numbers, e.g. "8-11" after "split", for
+/// when the kind of bet has already been picked. The error's range is within `input`.
+pub fn parse_bet_argument(wheel: Wheel, kind: &str, input: &str) -> Result<Bet, ParseError> {
+    let kind = kind.to_lowercase();
+    let hint = argument_hint(&kind).ok_or_else(|| ParseError {
+        message: format!("unknown bet '{}'", kind),
+        start: 0,
+        end: 0,
+    })?;
+    let text = input.trim();
+    if text.is_empty() {
+        return Err(ParseError {
+            message: format!("'{}' needs {}", kind, hint),
+            start: 0,
+            end: input.len(),
+        });
+    }
+    bet_with_argument(wheel, &kind, Token::within(input, text))
+}
+
+/// What follows a bet of `kind` when it takes numbers, or `None` for any other kind.
+fn argument_hint(kind: &str) -> Option<&'static str> {
+    Some(match kind {
+        "dozen" | "d" => "a dozen from 1 to 3",
+        "column" | "co" => "a column from 1 to 3",
+        "n" | "number" | "straight" => "a number, e.g. 17 or 00",
+        "split" | "s" => "two numbers next to each other, e.g. 8-11",
+        "corner" | "cr" => "the opposite corners of a block, e.g. 17-21",
+        "street" | "st" => "the first number of a row, e.g. 7",
+        "line" | "six-line" | "sl" => "the first number of the upper row, e.g. 7",
+        _ => return None,
+    })
+}
+
+/// Reads the bet of `kind`, one that `argument_hint` knows, from the numbers in `token`.
+fn bet_with_argument(wheel: Wheel, kind: &str, token: Token) -> Result<Bet, ParseError> {
+    Ok(match kind {
+        "dozen" | "d" => Bet::Dozen(token.one_to_three("dozen")?),
+        "column" | "co" => Bet::Column(token.one_to_three("column")?),
+        "n" | "number" | "straight" => Bet::Straight(token.pocket(wheel)?),
+        "split" | "s" => {
+            let (a, b) = token.pair()?;
+            Bet::split(wheel, a.pocket(wheel)?, b.pocket(wheel)?).map_err(|e| token.error(e))?
+        }
+        "corner" | "cr" => {
+            let (low, high) = token.pair()?;
+            Bet::corner(low.number()?, high.number()?).map_err(|e| token.error(e))?
+        }
+        "street" | "st" => parse_rows(token, 3, Bet::street)?,
+        "line" | "six-line" | "sl" => parse_rows(token, 6, Bet::six_line)?,
+        _ => unreachable!("'{}' is not a bet on numbers", kind),
+    })
+}
+
+/// Reads a street or six line of `size` numbers, written as its first number or as its
+/// first and last, e.g. "7" or "7-9".
+fn parse_rows(
+    token: Token,
+    size: u8,
+    bet: fn(u8) -> Result<Bet, String>,
+) -> Result<Bet, ParseError> {
+    let first = if token.text.contains('-') {
+        let (first, last) = token.pair()?;
+        let (first, last) = (first.number()?, last.number()?);
+        if last != first + size - 1 {
+            return Err(token.error(format!(
+                "{}-{} does not cover {} numbers in a row",
+                first, last, size
+            )));
+        }
+        first
+    } else {
+        token.number()?
+    };
+    bet(first).map_err(|e| token.error(e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bets() {
+        let bets = parse_bets(
+            Wheel::American,
+            "10 red; 5 split 8-11; 2 corner 17-21; 25 dozen 2; 1 n 00",
+        );
+        assert_eq!(
+            bets,
+            Ok(vec![
+                (Bet::Red, 10),
+                (Bet::Split(Pocket::Number(8), Pocket::Number(11)), 5),
+                (Bet::Corner(17), 2),
+                (Bet::Dozen(2), 25),
+                (Bet::Straight(Pocket::DoubleZero), 1),
+            ])
+        );
+        assert_eq!(
+            parse_bets(Wheel::European, "3 st 7-9; 4 sl 31; 1 b;"),
+            Ok(vec![
+                (Bet::Street(7), 3),
+                (Bet::SixLine(31), 4),
+                (Bet::Basket, 1)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_errors_point_at_the_token() {
+        let error_at = |input: &str| {
+            let error = parse_bets(Wheel::European, input).unwrap_err();
+            (input[error.start..error.end].to_string(), error.message)
+        };
+        assert_eq!(error_at("10 red; x black").0, "x");
+        assert_eq!(error_at("10 redd").0, "redd");
+        assert_eq!(error_at("5 split 8-12").0, "8-12");
+        assert_eq!(error_at("5 split 8-4x").0, "4x");
+        assert_eq!(error_at("1 n 00").0, "00");
+        assert_eq!(error_at("2 dozen 4").0, "4");
+        assert_eq!(
+            error_at("2 dozen x").1,
+            "expected a dozen from 1 to 3, got 'x'"
+        );
+        assert_eq!(
+            error_at("2 column 300").1,
+            "expected a column from 1 to 3, got '300'"
+        );
+        assert_eq!(error_at("2 red 4").0, "4");
+        assert_eq!(error_at("2 street 7-10").0, "7-10");
+        let (token, message) = error_at("10 red; 5 split");
+        assert_eq!(token, "");
+        assert_eq!(
+            message,
+            "'split' needs two numbers next to each other, e.g. 8-11"
+        );
+        assert!(parse_bets(Wheel::European, " ; ").is_err());
+
+        let input = "10 red; 5 corner 18-22";
+        let error = parse_bets(Wheel::European, input).unwrap_err();
+        assert_eq!(error.underline(input), "                 ^^^^^");
+    }
+
+    #[test]
+    fn test_parse_bet_argument() {
+        let wheel = Wheel::American;
+        assert_eq!(
+            parse_bet_argument(wheel, "split", " 00-3\n"),
+            Ok(Bet::Split(Pocket::DoubleZero, Pocket::Number(3)))
+        );
+        assert_eq!(
+            parse_bet_argument(wheel, "corner", "21-17"),
+            Ok(Bet::Corner(17))
+        );
+        assert_eq!(
+            parse_bet_argument(wheel, "street", "7-9"),
+            Ok(Bet::Street(7))
+        );
+        let input = "8-4x";
+        let error = parse_bet_argument(wheel, "split", input).unwrap_err();
+        assert_eq!(&input[error.start..error.end], "4x");
+        assert_eq!(
+            parse_bet_argument(wheel, "line", "").unwrap_err().message,
+            "'line' needs the first number of the upper row, e.g. 7"
+        );
+    }
+}
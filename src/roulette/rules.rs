@@ -0,0 +1,148 @@
+use super::{Pocket, Wheel};
+use std::fmt;
+use std::str::FromStr;
+
+/// What happens to the even-money bets (red/black, even/odd, 1-18/19-36) when the ball
+/// lands on zero. The French rules are only dealt on a single-zero wheel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ZeroRule {
+    /// The bets lose like any other.
+    None,
+    /// La Partage: half the stake is returned and the house keeps the other half.
+    LaPartage,
+    /// En Prison: the bet stays on the table for the next spin. If it wins then, the
+    /// stake is returned without winnings. If it loses, or zero comes up again, it is lost.
+    EnPrison,
+}
+
+impl ZeroRule {
+    pub fn allowed_on(self, wheel: Wheel) -> bool {
+        self == ZeroRule::None || wheel == Wheel::European
+    }
+
+    /// How an even-money bet fares on a spin of `result`, where `won` is whether the bet
+    /// covers it and `imprisoned` whether the bet was held over from a zero last spin.
+    pub fn settle_even_money(self, won: bool, result: Pocket, imprisoned: bool) -> Outcome {
+        match (imprisoned, won) {
+            (true, true) => Outcome::Released,
+            (true, false) => Outcome::Lose,
+            (false, true) => Outcome::Win,
+            (false, false) if !result.is_zero() => Outcome::Lose,
+            (false, false) => match self {
+                ZeroRule::None => Outcome::Lose,
+                ZeroRule::LaPartage => Outcome::Partage,
+                ZeroRule::EnPrison => Outcome::Imprisoned,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ZeroRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            ZeroRule::None => "none",
+            ZeroRule::LaPartage => "la-partage",
+            ZeroRule::EnPrison => "en-prison",
+        })
+    }
+}
+
+impl FromStr for ZeroRule {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "none" | "no" => Ok(ZeroRule::None),
+            "la-partage" | "partage" => Ok(ZeroRule::LaPartage),
+            "en-prison" | "prison" => Ok(ZeroRule::EnPrison),
+            _ => Err(format!(
+                "zero rule must be none, la-partage or en-prison, got '{}'",
+                s.trim()
+            )),
+        }
+    }
+}
+
+/// How a bet fares on one spin. Only even-money bets can be split or imprisoned by the
+/// zero rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Outcome {
+    Win,
+    Lose,
+    /// Zero under La Partage: half the stake comes back.
+    Partage,
+    /// Zero under En Prison: the bet is held for the next spin.
+    Imprisoned,
+    /// An imprisoned bet that won: the stake comes back with nothing more.
+    Released,
+}
+
+impl Outcome {
+    /// The change to the player's balance for a bet of `bet` paying `payout` to 1, or
+    /// `None` while the bet is still riding in prison. La Partage returns half the stake,
+    /// rounded down.
+    pub fn net(self, bet: i32, payout: i32) -> Option<i32> {
+        match self {
+            Outcome::Win => Some(bet * payout),
+            Outcome::Lose => Some(-bet),
+            Outcome::Partage => Some(-(bet - bet / 2)),
+            Outcome::Imprisoned => None,
+            Outcome::Released => Some(0),
+        }
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Outcome::Win => "won",
+            Outcome::Lose => "lost",
+            Outcome::Partage => "half back (la partage)",
+            Outcome::Imprisoned => "en prison",
+            Outcome::Released => "released from prison",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_zero_rules() {
+        let zero = Pocket::Zero;
+        let seven = Pocket::Number(7);
+        assert_eq!(
+            ZeroRule::None.settle_even_money(false, zero, false),
+            Outcome::Lose
+        );
+        assert_eq!(
+            ZeroRule::LaPartage.settle_even_money(false, zero, false),
+            Outcome::Partage
+        );
+        assert_eq!(
+            ZeroRule::LaPartage.settle_even_money(false, seven, false),
+            Outcome::Lose
+        );
+        assert_eq!(
+            ZeroRule::EnPrison.settle_even_money(false, zero, false),
+            Outcome::Imprisoned
+        );
+        assert_eq!(
+            ZeroRule::EnPrison.settle_even_money(true, seven, true),
+            Outcome::Released
+        );
+        // A second zero loses the imprisoned bet
+        assert_eq!(
+            ZeroRule::EnPrison.settle_even_money(false, zero, true),
+            Outcome::Lose
+        );
+        assert_eq!(Outcome::Win.net(10, 1), Some(10));
+        assert_eq!(Outcome::Partage.net(10, 1), Some(-5));
+        assert_eq!(Outcome::Partage.net(5, 1), Some(-3));
+        assert_eq!(Outcome::Imprisoned.net(5, 1), None);
+        assert!(!ZeroRule::EnPrison.allowed_on(Wheel::American));
+        assert!(ZeroRule::LaPartage.allowed_on(Wheel::European));
+        assert_eq!("La Partage".parse(), Ok(ZeroRule::LaPartage));
+    }
+}
@@ -0,0 +1,128 @@
+use super::{Bet, Outcome, Pocket, Wheel, ZeroRule};
+
+/// Money on one bet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Wager {
+    pub bet: Bet,
+    pub amount: i32,
+    /// Held over from a zero under En Prison, so it can only be returned or lost.
+    pub imprisoned: bool,
+}
+
+/// How one wager on the slip was settled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Settlement {
+    pub wager: Wager,
+    pub outcome: Outcome,
+    /// The change to the player's balance, nothing while the wager sits in prison.
+    pub net: i32,
+}
+
+/// Every wager the player has on the table for the next spin.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BetSlip {
+    wagers: Vec<Wager>,
+}
+
+impl BetSlip {
+    pub fn new() -> Self {
+        BetSlip::default()
+    }
+
+    /// Adds `amount` on `bet`, on top of anything already on the slip.
+    pub fn add(&mut self, bet: Bet, amount: i32) {
+        self.wagers.push(Wager {
+            bet,
+            amount,
+            imprisoned: false,
+        });
+    }
+
+    pub fn wagers(&self) -> &[Wager] {
+        &self.wagers
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.wagers.is_empty()
+    }
+
+    /// Everything at risk on the slip, imprisoned wagers included.
+    pub fn total(&self) -> i32 {
+        self.wagers.iter().map(|wager| wager.amount).sum()
+    }
+
+    /// Settles every wager against a single spin of `result`. Wagers sent to prison stay on
+    /// the slip for the next spin, and the rest are taken off it.
+    pub fn settle(&mut self, wheel: Wheel, zero_rule: ZeroRule, result: Pocket) -> Vec<Settlement> {
+        let settlements: Vec<Settlement> = self
+            .wagers
+            .iter()
+            .map(|&wager| {
+                let outcome = wager.bet.settle(wheel, zero_rule, result, wager.imprisoned);
+                Settlement {
+                    wager,
+                    outcome,
+                    net: outcome
+                        .net(wager.amount, wager.bet.payout(wheel))
+                        .unwrap_or(0),
+                }
+            })
+            .collect();
+        self.wagers = settlements
+            .iter()
+            .filter(|settlement| settlement.outcome == Outcome::Imprisoned)
+            .map(|settlement| Wager {
+                imprisoned: true,
+                ..settlement.wager
+            })
+            .collect();
+        settlements
+    }
+}
+
+/// The change to the player's balance over a round of settlements.
+pub fn net_change(settlements: &[Settlement]) -> i32 {
+    settlements.iter().map(|settlement| settlement.net).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slip_settles_every_wager_on_one_spin() {
+        let wheel = Wheel::European;
+        let mut slip = BetSlip::new();
+        slip.add(Bet::Red, 10);
+        slip.add(Bet::Straight(Pocket::Number(17)), 2);
+        slip.add(Bet::Corner(17), 5);
+        slip.add(Bet::Dozen(2), 25);
+        assert_eq!(slip.total(), 42);
+
+        // 17 is black and in the second dozen
+        let settlements = slip.settle(wheel, ZeroRule::None, Pocket::Number(17));
+        let nets: Vec<i32> = settlements.iter().map(|s| s.net).collect();
+        assert_eq!(nets, [-10, 70, 40, 50]);
+        assert_eq!(net_change(&settlements), 150);
+        assert!(slip.is_empty());
+    }
+
+    #[test]
+    fn test_imprisoned_wagers_stay_on_the_slip() {
+        let wheel = Wheel::European;
+        let mut slip = BetSlip::new();
+        slip.add(Bet::Even, 10);
+        slip.add(Bet::Straight(Pocket::Number(4)), 1);
+
+        let settlements = slip.settle(wheel, ZeroRule::EnPrison, Pocket::Zero);
+        assert_eq!(net_change(&settlements), -1);
+        assert_eq!(slip.wagers().len(), 1);
+        assert!(slip.wagers()[0].imprisoned);
+        assert_eq!(slip.total(), 10);
+
+        let settlements = slip.settle(wheel, ZeroRule::EnPrison, Pocket::Number(4));
+        assert_eq!(settlements[0].outcome, Outcome::Released);
+        assert_eq!(net_change(&settlements), 0);
+        assert!(slip.is_empty());
+    }
+}
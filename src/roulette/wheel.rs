@@ -0,0 +1,235 @@
+use rand::seq::SliceRandom;
+use rand::Rng;
+use std::fmt;
+use std::str::FromStr;
+
+/// The numbers coloured red on the table and the wheel. The rest of 1 to 36 are black.
+pub const RED_NUMBERS: [u8; 18] = [
+    1, 3, 5, 7, 9, 12, 14, 16, 18, 19, 21, 23, 25, 27, 30, 32, 34, 36,
+];
+
+/// A pocket the ball can land in: one of the numbers 1 to 36 or a green zero.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Pocket {
+    Zero,
+    DoubleZero,
+    TripleZero,
+    Number(u8),
+}
+
+impl Pocket {
+    /// The pocket's number, or `None` for the zeros.
+    pub fn number(self) -> Option<u8> {
+        match self {
+            Pocket::Number(number) => Some(number),
+            _ => None,
+        }
+    }
+
+    pub fn is_zero(self) -> bool {
+        self.number().is_none()
+    }
+
+    pub fn colour(self) -> Colour {
+        match self.number() {
+            None => Colour::Green,
+            Some(number) if RED_NUMBERS.contains(&number) => Colour::Red,
+            Some(_) => Colour::Black,
+        }
+    }
+}
+
+impl fmt::Display for Pocket {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Pocket::Zero => f.pad("0"),
+            Pocket::DoubleZero => f.pad("00"),
+            Pocket::TripleZero => f.pad("000"),
+            Pocket::Number(number) => f.pad(&number.to_string()),
+        }
+    }
+}
+
+impl FromStr for Pocket {
+    type Err = String;
+
+    /// Reads "0", "00", "000" or a number from 1 to 36.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "0" => Ok(Pocket::Zero),
+            "00" => Ok(Pocket::DoubleZero),
+            "000" => Ok(Pocket::TripleZero),
+            number => match number.parse() {
+                Ok(number @ 1..=36) => Ok(Pocket::Number(number)),
+                _ => Err(format!("'{}' is not a roulette number", number)),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Red,
+    Black,
+    Green,
+}
+
+/// Clockwise around a single-zero wheel, starting from 0.
+const EUROPEAN_ORDER: &str = "0 32 15 19 4 21 2 25 17 34 6 27 13 36 11 30 8 23 10 5 24 16 33 1 20 \
+                              14 31 9 22 18 29 7 28 12 35 3 26";
+
+/// Clockwise around a double-zero wheel, starting from 0. 00 sits opposite 0.
+const AMERICAN_ORDER: &str = "0 28 9 26 30 11 7 20 32 17 5 22 34 15 3 24 36 13 1 00 27 10 25 29 \
+                              12 8 19 31 18 6 21 33 16 4 23 35 14 2";
+
+/// The double-zero order with 000 set in beside 0.
+const TRIPLE_ZERO_ORDER: &str = "0 000 28 9 26 30 11 7 20 32 17 5 22 34 15 3 24 36 13 1 00 27 10 \
+                                 25 29 12 8 19 31 18 6 21 33 16 4 23 35 14 2";
+
+/// The kind of wheel at the table, which decides how many green zeros there are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Wheel {
+    /// A single 0.
+    European,
+    /// 0 and 00.
+    American,
+    /// 0, 00 and 000.
+    TripleZero,
+}
+
+impl Wheel {
+    pub const ALL: [Wheel; 3] = [Wheel::European, Wheel::American, Wheel::TripleZero];
+
+    /// The green pockets, in the order they sit across the top of the table layout.
+    pub fn zeros(self) -> &'static [Pocket] {
+        match self {
+            Wheel::European => &[Pocket::Zero],
+            Wheel::American => &[Pocket::Zero, Pocket::DoubleZero],
+            Wheel::TripleZero => &[Pocket::Zero, Pocket::DoubleZero, Pocket::TripleZero],
+        }
+    }
+
+    /// Every pocket in the order they sit around the wheel, clockwise from 0.
+    pub fn order(self) -> Vec<Pocket> {
+        let order = match self {
+            Wheel::European => EUROPEAN_ORDER,
+            Wheel::American => AMERICAN_ORDER,
+            Wheel::TripleZero => TRIPLE_ZERO_ORDER,
+        };
+        order
+            .split_whitespace()
+            .map(|pocket| pocket.parse().unwrap())
+            .collect()
+    }
+
+    pub fn contains(self, pocket: Pocket) -> bool {
+        pocket.number().is_some() || self.zeros().contains(&pocket)
+    }
+
+    /// Reads a pocket, checking it is on this wheel.
+    pub fn parse_pocket(self, s: &str) -> Result<Pocket, String> {
+        let pocket: Pocket = s.parse()?;
+        if !self.contains(pocket) {
+            return Err(format!("there is no {} on a {} wheel", pocket, self));
+        }
+        Ok(pocket)
+    }
+
+    /// Where the ball lands, every pocket being equally likely.
+    pub fn spin(self, rng: &mut impl Rng) -> Pocket {
+        *self.order().choose(rng).unwrap()
+    }
+
+    /// `pocket` with `each_side` of its neighbours on the wheel either side of it.
+    pub fn neighbours(self, pocket: Pocket, each_side: usize) -> Vec<Pocket> {
+        let order = self.order();
+        let index = order.iter().position(|&p| p == pocket).unwrap_or(0);
+        (0..=2 * each_side)
+            .map(|offset| order[(index + order.len() + offset - each_side) % order.len()])
+            .collect()
+    }
+
+    /// What the zeros take from every bet paying 35:1, as a fraction of the stake.
+    pub fn house_edge(self) -> f64 {
+        self.zeros().len() as f64 / self.order().len() as f64
+    }
+}
+
+impl fmt::Display for Wheel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Wheel::European => "European",
+            Wheel::American => "American",
+            Wheel::TripleZero => "triple-zero",
+        })
+    }
+}
+
+impl FromStr for Wheel {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "european" | "single-zero" | "single" => Ok(Wheel::European),
+            "american" | "double-zero" | "double" => Ok(Wheel::American),
+            "triple-zero" | "triple" => Ok(Wheel::TripleZero),
+            _ => Err(format!(
+                "wheel must be european, american or triple-zero, got '{}'",
+                s.trim()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+
+    #[test]
+    fn test_wheel_orders() {
+        for wheel in Wheel::ALL {
+            let order = wheel.order();
+            assert_eq!(order.len(), 36 + wheel.zeros().len(), "{}", wheel);
+            let mut sorted = order.to_vec();
+            sorted.sort();
+            sorted.dedup();
+            assert_eq!(sorted.len(), order.len(), "{} repeats a pocket", wheel);
+            assert!(order.iter().all(|&pocket| wheel.contains(pocket)));
+            // Numbers alternate red and black around the wheel, apart from the zeros
+            for pair in order.windows(2) {
+                if !pair[0].is_zero() && !pair[1].is_zero() {
+                    assert_ne!(pair[0].colour(), pair[1].colour(), "{} {:?}", wheel, pair);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_pockets() {
+        assert_eq!("00".parse(), Ok(Pocket::DoubleZero));
+        assert_eq!("17".parse(), Ok(Pocket::Number(17)));
+        assert!("37".parse::<Pocket>().is_err());
+        assert!(Wheel::European.parse_pocket("00").is_err());
+        assert_eq!(
+            Wheel::TripleZero.parse_pocket("000"),
+            Ok(Pocket::TripleZero)
+        );
+        assert_eq!(Pocket::Number(1).colour(), Colour::Red);
+        assert_eq!(Pocket::Number(2).colour(), Colour::Black);
+        assert_eq!(Pocket::DoubleZero.colour(), Colour::Green);
+        assert_eq!(
+            Wheel::European.neighbours(Pocket::Zero, 1),
+            vec![Pocket::Number(26), Pocket::Zero, Pocket::Number(32)]
+        );
+    }
+
+    #[test]
+    fn test_spin_lands_on_zero() {
+        let mut rng = thread_rng();
+        let spins: Vec<Pocket> = (0..5000).map(|_| Wheel::American.spin(&mut rng)).collect();
+        assert!(spins.contains(&Pocket::Zero));
+        assert!(spins.contains(&Pocket::DoubleZero));
+        assert!(spins.contains(&Pocket::Number(36)));
+    }
+}
@@ -0,0 +1,152 @@
+//! Three Card Poker: dealer qualification and the Ante, Play, Ante Bonus and Pair Plus
+//! payouts.
+
+use crate::cards::{Card, Rank};
+use crate::poker::{evaluate_three_card, ThreeCardCategory, ThreeCardValue};
+
+/// Paid on the ante to any player who plays a straight or better, whatever the dealer has.
+pub const ANTE_BONUS: [(ThreeCardCategory, u32); 3] = [
+    (ThreeCardCategory::StraightFlush, 5),
+    (ThreeCardCategory::ThreeOfAKind, 4),
+    (ThreeCardCategory::Straight, 1),
+];
+
+/// The 1-4-6-30-40 Pair Plus paytable, paid on the player's hand alone.
+pub const PAIR_PLUS: [(ThreeCardCategory, u32); 5] = [
+    (ThreeCardCategory::StraightFlush, 40),
+    (ThreeCardCategory::ThreeOfAKind, 30),
+    (ThreeCardCategory::Straight, 6),
+    (ThreeCardCategory::Flush, 4),
+    (ThreeCardCategory::Pair, 1),
+];
+
+/// The dealer needs Queen high or better to open; otherwise the ante is paid and the play
+/// bet pushes.
+pub fn dealer_qualifies(dealer: &ThreeCardValue) -> bool {
+    dealer.category > ThreeCardCategory::HighCard || dealer.ranks[0] >= Rank::Queen
+}
+
+/// The strategy that plays Queen-6-4 or better and folds anything less, which is as close
+/// to perfect as makes no difference.
+pub fn should_play(player: &ThreeCardValue) -> bool {
+    player.category > ThreeCardCategory::HighCard
+        || player.ranks >= [Rank::Queen, Rank::Six, Rank::Four]
+}
+
+/// What a paytable pays to one for a hand, if anything.
+pub fn pays(paytable: &[(ThreeCardCategory, u32)], hand: &ThreeCardValue) -> Option<u32> {
+    paytable
+        .iter()
+        .find(|&&(category, _)| category == hand.category)
+        .map(|&(_, pays)| pays)
+}
+
+/// The change to the balance from each wager of a hand. The play bet always matches the
+/// ante, and is only made when the player plays.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Settlement {
+    pub ante: i32,
+    pub play: i32,
+    pub ante_bonus: i32,
+    pub pair_plus: i32,
+}
+
+impl Settlement {
+    pub fn total(&self) -> i32 {
+        self.ante + self.play + self.ante_bonus + self.pair_plus
+    }
+}
+
+/// Settles a hand of `ante` and an optional Pair Plus bet (0 for none). Folding loses the
+/// ante but leaves Pair Plus to be settled, since it only looks at the player's cards.
+pub fn settle(
+    player: &[Card],
+    dealer: &[Card],
+    ante: u32,
+    pair_plus: u32,
+    played: bool,
+) -> Settlement {
+    let player = evaluate_three_card(player);
+    let dealer = evaluate_three_card(dealer);
+    let ante = ante as i32;
+    let mut settlement = Settlement::default();
+    if pair_plus > 0 {
+        settlement.pair_plus = match pays(&PAIR_PLUS, &player) {
+            Some(pays) => (pair_plus * pays) as i32,
+            None => -(pair_plus as i32),
+        };
+    }
+    if !played {
+        settlement.ante = -ante;
+        return settlement;
+    }
+
+    settlement.ante_bonus = pays(&ANTE_BONUS, &player).map_or(0, |pays| ante * pays as i32);
+    (settlement.ante, settlement.play) = if !dealer_qualifies(&dealer) {
+        (ante, 0)
+    } else if player > dealer {
+        (ante, ante)
+    } else if player < dealer {
+        (-ante, -ante)
+    } else {
+        (0, 0)
+    };
+    settlement
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cards;
+
+    fn settle_hand(player: &str, dealer: &str, played: bool) -> Settlement {
+        settle(&cards(player), &cards(dealer), 10, 5, played)
+    }
+
+    #[test]
+    fn test_settle() {
+        // Dealer doesn't qualify: the ante wins and the play bet pushes
+        let hand = settle_hand("KC 9D 4H", "JC 8D 2S", true);
+        assert_eq!((hand.ante, hand.play, hand.pair_plus), (10, 0, -5));
+        // Dealer qualifies and loses to a pair
+        let hand = settle_hand("KC KD 4H", "QC 8D 2S", true);
+        assert_eq!((hand.ante, hand.play, hand.pair_plus), (10, 10, 5));
+        // A straight beats the dealer and earns both bonuses
+        let hand = settle_hand("5C 6D 7H", "AC AD 9S", true);
+        assert_eq!(
+            hand,
+            Settlement {
+                ante: 10,
+                play: 10,
+                ante_bonus: 10,
+                pair_plus: 30
+            }
+        );
+        let hand = settle_hand("5C 6D 8H", "AC AD 9S", true);
+        assert_eq!(hand.total(), -25);
+        // A tie pushes
+        let hand = settle_hand("QC 6D 4H", "QH 6S 4C", true);
+        assert_eq!(hand.total(), -5);
+        // Folding loses the ante but Pair Plus is still paid
+        let hand = settle_hand("2H 2D 9C", "AC AD 9S", false);
+        assert_eq!(
+            hand,
+            Settlement {
+                ante: -10,
+                play: 0,
+                ante_bonus: 0,
+                pair_plus: 5
+            }
+        );
+    }
+
+    #[test]
+    fn test_strategy() {
+        let value = |s: &str| evaluate_three_card(&cards(s));
+        assert!(should_play(&value("QC 6D 4H")));
+        assert!(!should_play(&value("QC 6D 3H")));
+        assert!(should_play(&value("2C 2D 3H")));
+        assert!(dealer_qualifies(&value("QC 3D 2H")));
+        assert!(!dealer_qualifies(&value("JC TD 8H")));
+    }
+}
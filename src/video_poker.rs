@@ -0,0 +1,324 @@
+//! Video poker paytables and the expected value of each hold.
+
+use crate::cards::{create_deck, Card, Rank};
+use crate::poker::{evaluate, wild_category, Category};
+use std::fmt;
+use std::str::FromStr;
+
+/// A line of a paytable. Which lines a game pays depends on the paytable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WinningHand {
+    RoyalFlush,
+    NaturalRoyalFlush,
+    FourDeuces,
+    WildRoyalFlush,
+    FiveOfAKind,
+    StraightFlush,
+    FourAces,
+    /// Four 2s, 3s or 4s.
+    FourLow,
+    /// Four 5s through kings.
+    FourHigh,
+    FourOfAKind,
+    FullHouse,
+    Flush,
+    Straight,
+    ThreeOfAKind,
+    TwoPair,
+    JacksOrBetter,
+}
+
+/// What each winning hand pays for one coin, best hand first. Pays are "for one", so a
+/// pay of 1 returns the bet. Royal flushes pay 800 for one, as when playing max coins.
+const JACKS_OR_BETTER: [(WinningHand, u32); 9] = [
+    (WinningHand::RoyalFlush, 800),
+    (WinningHand::StraightFlush, 50),
+    (WinningHand::FourOfAKind, 25),
+    (WinningHand::FullHouse, 9),
+    (WinningHand::Flush, 6),
+    (WinningHand::Straight, 4),
+    (WinningHand::ThreeOfAKind, 3),
+    (WinningHand::TwoPair, 2),
+    (WinningHand::JacksOrBetter, 1),
+];
+
+const DEUCES_WILD: [(WinningHand, u32); 10] = [
+    (WinningHand::NaturalRoyalFlush, 800),
+    (WinningHand::FourDeuces, 200),
+    (WinningHand::WildRoyalFlush, 25),
+    (WinningHand::FiveOfAKind, 15),
+    (WinningHand::StraightFlush, 9),
+    (WinningHand::FourOfAKind, 5),
+    (WinningHand::FullHouse, 3),
+    (WinningHand::Flush, 2),
+    (WinningHand::Straight, 2),
+    (WinningHand::ThreeOfAKind, 1),
+];
+
+const DOUBLE_BONUS: [(WinningHand, u32); 11] = [
+    (WinningHand::RoyalFlush, 800),
+    (WinningHand::FourAces, 160),
+    (WinningHand::FourLow, 80),
+    (WinningHand::StraightFlush, 50),
+    (WinningHand::FourHigh, 50),
+    (WinningHand::FullHouse, 10),
+    (WinningHand::Flush, 7),
+    (WinningHand::Straight, 5),
+    (WinningHand::ThreeOfAKind, 3),
+    (WinningHand::TwoPair, 1),
+    (WinningHand::JacksOrBetter, 1),
+];
+
+/// A video poker game, named after its paytable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paytable {
+    /// 9/6 Jacks or Better: full house 9, flush 6. Returns 99.5% with perfect play.
+    JacksOrBetter,
+    /// Full pay Deuces Wild, where every two is wild. Returns 100.8% with perfect play.
+    DeucesWild,
+    /// 10/7 Double Bonus, with bigger pays for four of a kind. Returns 100.2% with
+    /// perfect play.
+    DoubleBonus,
+}
+
+impl Paytable {
+    pub const ALL: [Paytable; 3] = [
+        Paytable::JacksOrBetter,
+        Paytable::DeucesWild,
+        Paytable::DoubleBonus,
+    ];
+
+    /// Every winning hand and what it pays for one coin, best hand first.
+    pub fn lines(self) -> &'static [(WinningHand, u32)] {
+        match self {
+            Paytable::JacksOrBetter => &JACKS_OR_BETTER,
+            Paytable::DeucesWild => &DEUCES_WILD,
+            Paytable::DoubleBonus => &DOUBLE_BONUS,
+        }
+    }
+
+    /// The winning hand five cards make and what it pays for one coin, or `None` when
+    /// they pay nothing.
+    pub fn evaluate(self, cards: &[Card]) -> Option<(WinningHand, u32)> {
+        let winning_hand = self.winning_hand(cards)?;
+        self.lines()
+            .iter()
+            .find(|&&(line, _)| line == winning_hand)
+            .copied()
+    }
+
+    fn winning_hand(self, cards: &[Card]) -> Option<WinningHand> {
+        if self == Paytable::DeucesWild {
+            let deuces = cards.iter().filter(|card| card.rank == Rank::Two).count();
+            return match wild_category(cards, Rank::Two) {
+                _ if deuces == 4 => Some(WinningHand::FourDeuces),
+                Category::RoyalFlush if deuces == 0 => Some(WinningHand::NaturalRoyalFlush),
+                Category::RoyalFlush => Some(WinningHand::WildRoyalFlush),
+                Category::FiveOfAKind => Some(WinningHand::FiveOfAKind),
+                Category::StraightFlush => Some(WinningHand::StraightFlush),
+                Category::FourOfAKind => Some(WinningHand::FourOfAKind),
+                Category::FullHouse => Some(WinningHand::FullHouse),
+                Category::Flush => Some(WinningHand::Flush),
+                Category::Straight => Some(WinningHand::Straight),
+                Category::ThreeOfAKind => Some(WinningHand::ThreeOfAKind),
+                _ => None,
+            };
+        }
+        let value = evaluate(cards);
+        let quads = value.ranks[0];
+        match value.category {
+            Category::RoyalFlush => Some(WinningHand::RoyalFlush),
+            Category::StraightFlush => Some(WinningHand::StraightFlush),
+            Category::FourOfAKind if self == Paytable::DoubleBonus => Some(match quads {
+                Rank::Ace => WinningHand::FourAces,
+                Rank::Two | Rank::Three | Rank::Four => WinningHand::FourLow,
+                _ => WinningHand::FourHigh,
+            }),
+            Category::FourOfAKind => Some(WinningHand::FourOfAKind),
+            Category::FullHouse => Some(WinningHand::FullHouse),
+            Category::Flush => Some(WinningHand::Flush),
+            Category::Straight => Some(WinningHand::Straight),
+            Category::ThreeOfAKind => Some(WinningHand::ThreeOfAKind),
+            Category::TwoPair => Some(WinningHand::TwoPair),
+            Category::Pair if value.ranks[0] >= Rank::Jack => Some(WinningHand::JacksOrBetter),
+            _ => None,
+        }
+    }
+
+    /// The average pay for one coin from keeping the cards of `hand` picked out by `hold`
+    /// (bit `i` keeps card `i`) and drawing the rest from the 47 cards not dealt.
+    pub fn hold_ev(self, hand: &[Card; 5], hold: u8) -> f64 {
+        let deck: Vec<Card> = create_deck()
+            .into_iter()
+            .filter(|card| !hand.contains(card))
+            .collect();
+        let mut draw = [hand[0]; 5];
+        let mut held = 0;
+        for (index, &card) in hand.iter().enumerate() {
+            if hold & (1 << index) != 0 {
+                draw[held] = card;
+                held += 1;
+            }
+        }
+        let (mut total, mut count) = (0u64, 0u64);
+        for_each_draw(&deck, &mut draw, held, &mut |cards| {
+            total += self.evaluate(cards).map_or(0, |(_, pays)| pays) as u64;
+            count += 1;
+        });
+        total as f64 / count as f64
+    }
+
+    /// The best of all 32 ways to hold `hand` and its expected pay for one coin. On equal
+    /// EVs the hold keeping more cards wins.
+    pub fn best_hold(self, hand: &[Card; 5]) -> (u8, f64) {
+        (0..32u8)
+            .rev()
+            .map(|hold| (hold, self.hold_ev(hand, hold)))
+            .fold((0, f64::MIN), |best, (hold, ev)| {
+                let more_cards = hold.count_ones() > best.0.count_ones();
+                if ev > best.1 + 1e-12 || (ev > best.1 - 1e-12 && more_cards) {
+                    (hold, ev)
+                } else {
+                    best
+                }
+            })
+    }
+}
+
+/// Calls `f` with every way of filling `hand[filled..]` from `deck`, ignoring order.
+fn for_each_draw(deck: &[Card], hand: &mut [Card; 5], filled: usize, f: &mut impl FnMut(&[Card])) {
+    if filled == hand.len() {
+        f(hand);
+        return;
+    }
+    let still_needed = hand.len() - filled;
+    for index in 0..=deck.len() - still_needed {
+        hand[filled] = deck[index];
+        for_each_draw(&deck[index + 1..], hand, filled + 1, f);
+    }
+}
+
+impl fmt::Display for WinningHand {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.pad(match self {
+            WinningHand::RoyalFlush => "Royal flush",
+            WinningHand::NaturalRoyalFlush => "Natural royal flush",
+            WinningHand::FourDeuces => "Four deuces",
+            WinningHand::WildRoyalFlush => "Wild royal flush",
+            WinningHand::FiveOfAKind => "Five of a kind",
+            WinningHand::StraightFlush => "Straight flush",
+            WinningHand::FourAces => "Four aces",
+            WinningHand::FourLow => "Four 2s, 3s or 4s",
+            WinningHand::FourHigh => "Four 5s through kings",
+            WinningHand::FourOfAKind => "Four of a kind",
+            WinningHand::FullHouse => "Full house",
+            WinningHand::Flush => "Flush",
+            WinningHand::Straight => "Straight",
+            WinningHand::ThreeOfAKind => "Three of a kind",
+            WinningHand::TwoPair => "Two pair",
+            WinningHand::JacksOrBetter => "Jacks or better",
+        })
+    }
+}
+
+impl fmt::Display for Paytable {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Paytable::JacksOrBetter => "9/6 Jacks or Better",
+            Paytable::DeucesWild => "Deuces Wild",
+            Paytable::DoubleBonus => "10/7 Double Bonus",
+        })
+    }
+}
+
+impl FromStr for Paytable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace(['_', ' '], "-").as_str() {
+            "jacks-or-better" | "jacks" | "job" => Ok(Paytable::JacksOrBetter),
+            "deuces-wild" | "deuces" => Ok(Paytable::DeucesWild),
+            "double-bonus" | "bonus" => Ok(Paytable::DoubleBonus),
+            _ => Err(format!(
+                "unknown paytable '{}', expected jacks-or-better, deuces-wild or double-bonus",
+                s.trim()
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cards::cards;
+
+    fn hand(s: &str) -> [Card; 5] {
+        cards(s).try_into().unwrap()
+    }
+
+    fn pays(paytable: Paytable, s: &str) -> Option<u32> {
+        paytable.evaluate(&cards(s)).map(|(_, pays)| pays)
+    }
+
+    #[test]
+    fn test_jacks_or_better() {
+        let job = Paytable::JacksOrBetter;
+        assert_eq!(pays(job, "TH JH QH KH AH"), Some(800));
+        assert_eq!(pays(job, "7C 7D 7H 2S 2C"), Some(9));
+        assert_eq!(pays(job, "JC JD 3H 2S 4C"), Some(1));
+        assert_eq!(pays(job, "TC TD 3H 2S 4C"), None);
+        assert_eq!(pays(job, "AC 2D 3H 4S 5C"), Some(4));
+    }
+
+    #[test]
+    fn test_deuces_wild() {
+        let deuces = Paytable::DeucesWild;
+        assert_eq!(pays(deuces, "2C 2D 2H 2S AC"), Some(200));
+        assert_eq!(pays(deuces, "TH JH QH KH AH"), Some(800));
+        assert_eq!(pays(deuces, "2C JH QH KH AH"), Some(25));
+        assert_eq!(pays(deuces, "2C 2D 9H 9S 9C"), Some(15));
+        assert_eq!(pays(deuces, "2C 9D 9H KS 4C"), Some(1));
+        assert_eq!(pays(deuces, "KC KD 9H 9S 4C"), None);
+    }
+
+    #[test]
+    fn test_double_bonus() {
+        let bonus = Paytable::DoubleBonus;
+        assert_eq!(pays(bonus, "AC AD AH AS 4C"), Some(160));
+        assert_eq!(
+            bonus.evaluate(&cards("9H TH JH QH KH")),
+            Some((WinningHand::StraightFlush, 50))
+        );
+        assert_eq!(pays(bonus, "3C 3D 3H 3S 4C"), Some(80));
+        assert_eq!(pays(bonus, "KC KD KH KS 4C"), Some(50));
+        assert_eq!(pays(bonus, "KC KD 4H 4S 5C"), Some(1));
+        assert_eq!("double bonus".parse(), Ok(bonus));
+    }
+
+    #[test]
+    fn test_lines_are_best_hand_first() {
+        for paytable in Paytable::ALL {
+            let lines = paytable.lines();
+            assert!(
+                lines.windows(2).all(|pair| pair[0].1 >= pair[1].1),
+                "{}",
+                paytable
+            );
+        }
+    }
+
+    #[test]
+    fn test_hold_ev() {
+        let job = Paytable::JacksOrBetter;
+        let royal = hand("TH JH QH KH AH");
+        assert_eq!(job.hold_ev(&royal, 0b11111), 800.0);
+        // Four to a flush: nine hearts make a flush, three aces and three kings a pair
+        let four_flush = hand("AH KH 7H 3H 9C");
+        assert_eq!(job.hold_ev(&four_flush, 0b01111), 60.0 / 47.0);
+        // Drawing two to a pair of jacks: the 16.5% of draws that improve pay more
+        let jacks = hand("JH JS 7H 3C 9D");
+        let pair_ev = job.hold_ev(&jacks, 0b00011);
+        assert!(pair_ev > job.hold_ev(&jacks, 0b00111));
+        assert!((pair_ev - 1.536).abs() < 0.001, "{}", pair_ev);
+    }
+}